@@ -1,8 +1,17 @@
+use std::path::PathBuf;
 use std::str::FromStr;
 use std::sync::atomic::AtomicBool;
 use std::time::Duration;
 
-use clap::{ArgAction, Parser};
+use aoc_utils::{
+    baseline::{self, Baseline},
+    config,
+    leaderboard::{self, Leaderboard},
+    progress::{self, TqdmHook},
+    runner::{self, RunOptions, Schedule},
+    utils::file::Fetch,
+};
+use clap::{builder::RangedU64ValueParser, ArgAction, Parser, Subcommand};
 use fern::colors::{Color, ColoredLevelConfig};
 use miette::{IntoDiagnostic, MietteHandlerOpts, Result, WrapErr};
 use mimalloc::MiMalloc;
@@ -108,11 +117,131 @@ struct Args {
 
     #[arg(short = 't', long = "topn", default_value_t = 10)]
     topn: usize,
+
+    /// Run one day at a time, for steadier timings
+    #[arg(short, long)]
+    serial: bool,
+
+    /// Give up on a stage after this many seconds, unless its day sets its own budget
+    #[arg(long, value_name = "SECONDS", value_parser = runner::parse_timeout)]
+    timeout: Option<Duration>,
+
+    /// Show progress bars for the stages that report progress
+    #[arg(long)]
+    progress: bool,
+
+    /// Download inputs again, even the ones that are already cached
+    #[arg(long, conflicts_with = "offline")]
+    refetch: bool,
+
+    /// Never download anything; days without a cached input fail
+    #[arg(long)]
+    offline: bool,
+
+    /// Run another account's inputs, from the profiles in aoc.toml
+    #[arg(long)]
+    profile: Option<String>,
+
+    /// Play the chosen days in the terminal instead of timing them
+    #[arg(long)]
+    viz: bool,
+
+    /// Save the stage timings as this machine's baseline for the current git revision
+    #[arg(long)]
+    save_baseline: bool,
+
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Compare two saved baselines and flag the stages that got slower
+    Compare {
+        /// The revision to compare against
+        before: String,
+        /// The revision to check, the working tree by default
+        after: Option<String>,
+        /// How many percent slower a stage may get before it's flagged
+        #[arg(long, default_value_t = 10.0)]
+        threshold: f64,
+    },
+    /// Print a `sample_case!` for a day, from the examples on its puzzle page
+    Samples {
+        /// The day to read the examples of
+        day: usize,
+    },
+    /// Show a private leaderboard's standings, and when everyone solved the chosen days
+    Leaderboard {
+        /// The leaderboard's id, the number at the end of its URL
+        #[arg(required_unless_present = "file")]
+        id: Option<u64>,
+        /// Read the leaderboard from a JSON file instead of downloading it
+        #[arg(long)]
+        file: Option<PathBuf>,
+        /// The days to list completion times for, in local time
+        #[arg(short, long, action = ArgAction::Append, value_parser = RangedU64ValueParser::<usize>::new().range(1..=25))]
+        days: Vec<usize>,
+    },
+}
+
+impl Args {
+    fn fetch(&self) -> Fetch {
+        if self.offline {
+            Fetch::Never
+        } else if self.refetch {
+            Fetch::Always
+        } else {
+            Fetch::Missing
+        }
+    }
 }
 
 fn main() -> Result<()> {
     let args = Args::parse();
+    let fetch = args.fetch();
     let days: Vec<usize> = args.days;
+    match args.command {
+        Some(Command::Compare {
+            before,
+            after,
+            threshold,
+        }) => return baseline::compare(aoc2019::YEAR, &before, after.as_deref(), threshold),
+        Some(Command::Samples { day }) => {
+            let profile = config::profile(args.profile.as_deref())?;
+            print!(
+                "{}",
+                aoc_utils::load_puzzle(&profile, aoc2019::YEAR, day, fetch)?.sample_stub()
+            );
+            return Ok(());
+        }
+        Some(Command::Leaderboard { id, file, days }) => {
+            let leaderboard = match (file, id) {
+                (Some(file), _) => Leaderboard::from_file(&file)?,
+                (None, Some(id)) => {
+                    let profile = config::profile(args.profile.as_deref())?;
+                    leaderboard::load(&profile, aoc2019::YEAR, id, fetch)?
+                }
+                (None, None) => unreachable!("clap asks for an id or a file"),
+            };
+            print!("{}", leaderboard.standings());
+            for day in days {
+                println!();
+                print!("{}", leaderboard.day(day, chrono::Local)?);
+            }
+            return Ok(());
+        }
+        None => {}
+    }
+    if args.viz {
+        // The logger would draw over the terminal UI.
+        let options = RunOptions {
+            fetch,
+            profile: args.profile.clone(),
+            ..RunOptions::default()
+        };
+        return aoc2019::visualize_days(days, &options);
+    }
     setup_logger()?;
     AllocationRegistry::set_global_tracker(StdoutTracker)
         .expect("no other global tracker should be set yet");
@@ -120,21 +249,46 @@ fn main() -> Result<()> {
         PANIC_ON_ALLOCATE.store(true, std::sync::atomic::Ordering::SeqCst);
     }
 
-    let mut times = aoc2019::run_all(days, args.track_allocations)?;
+    // Allocation tracking is global, so it can't tell parallel days apart, and baselines should
+    // be timed without the other days competing for the CPU.
+    let schedule = if args.serial || args.track_allocations || args.save_baseline {
+        Schedule::Serial
+    } else {
+        Schedule::Parallel
+    };
+    if args.progress {
+        progress::set_hook(TqdmHook::default())?;
+    }
+    let options = RunOptions {
+        schedule,
+        track: args.track_allocations,
+        timeout: args.timeout,
+        fetch,
+        profile: args.profile.clone(),
+    };
+    let reports = aoc2019::run_all(days, &options)?;
+    if args.save_baseline {
+        let revision = baseline::current_revision()?;
+        let times = reports.iter().flat_map(|r| &r.times);
+        let path = Baseline::from_times(times).save(aoc2019::YEAR, &revision)?;
+        log::info!("Saved baseline for {} to {}", revision, path.display());
+    }
 
-    let mut total_time = Duration::ZERO;
-    let mut count = 0;
+    let mut times = reports.iter().flat_map(|r| &r.times).collect::<Vec<_>>();
+    times.sort_by(|a, b| b.cmp(a));
+    let total_time: Duration = times.iter().map(|t| t.time).sum();
     log::info!("Most expensive {} Stages:", args.topn);
-    while let Some(time) = times.pop() {
-        total_time += time.time;
-        count += 1;
-        if count >= args.topn {
-            continue;
-        }
+    for time in times.iter().take(args.topn) {
         time.log(log::Level::Info)
     }
     println!();
     log::info!("Total Time: {:?}", total_time);
+    println!();
+    runner::log_summary(&reports);
 
+    let failed = reports.iter().filter(|r| !r.status.is_ok()).count();
+    if failed > 0 {
+        miette::bail!("{failed} day(s) failed");
+    }
     Ok(())
 }
@@ -2,8 +2,8 @@ use std::iter::zip;
 
 use itertools::Itertools;
 use miette::Result;
-use multiset::HashMultiSet;
 
+use aoc_utils::collections::multiset::HashMultiSet;
 use aoc_utils::Runner;
 
 pub struct Day;
@@ -43,7 +43,7 @@ impl Runner for Day {
             input.0.iter().copied(),
             HashMultiSet::from_iter(input.1.iter().copied()),
         );
-        Ok(left.map(|v| v * right.count_of(&v)).sum())
+        Ok(left.map(|v| v * right.count(&v)).sum())
     }
 }
 
@@ -4,6 +4,7 @@ use std::rc::Rc;
 use aoc_utils::{
     collections::bitset::{BitSet, Dim, Dimension, FromBitSetIndex, ToBitSetIndex},
     math::coord::Coord,
+    progress::Progress,
 };
 use hashbrown::HashSet;
 use miette::Result;
@@ -189,16 +190,20 @@ impl Runner for Day {
         let start = grid.guard;
         grid.take_walk(None);
 
-        let coords = grid
+        let mut coords = grid
             .visited
             .iter()
             .map(|Pair(c, _)| c)
             .collect::<HashSet<_>>();
+        // The guard is standing on the start, so nothing can go there.
+        coords.remove(&start);
 
+        let progress = Progress::current();
+        progress.start(Some(coords.len()));
         Ok(coords
             .par_iter()
-            .filter(|&c| *c != start)
             .filter(|&coord| {
+                progress.tick();
                 let mut grid = input.clone();
                 grid.take_walk(Some(*coord))
             })
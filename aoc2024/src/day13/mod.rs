@@ -1,5 +1,4 @@
 use miette::Result;
-use nalgebra::{Matrix2, Vector2};
 use winnow::{
     ascii::{dec_uint, line_ending},
     combinator::{opt, repeat, terminated},
@@ -9,19 +8,35 @@ use winnow::{
 };
 
 use aoc_utils::errors::ToMiette;
+use aoc_utils::math::linear::{self, Diophantine, Solution};
 use aoc_utils::Runner;
 
 pub struct Day;
 
 #[derive(Debug, Clone)]
 pub struct System {
-    vars: Matrix2<f64>,
-    prize: Vector2<f64>,
+    vars: [[i128; 2]; 2],
+    prize: [i128; 2],
 }
 
 impl System {
-    pub fn solve(&self) -> Option<Vector2<f64>> {
-        Some(self.vars.try_inverse()? * self.prize)
+    /// The nonnegative number of presses of each button that reaches the prize.
+    pub fn solve(&self) -> Option<[i128; 2]> {
+        match linear::solve(&self.vars, &self.prize) {
+            // Both equations say the same thing, so pick the cheapest way to satisfy the first.
+            Solution::Underdetermined { .. } => {
+                let [[ax, bx], _] = self.vars;
+                (ax > 0 && bx > 0)
+                    .then(|| Diophantine::new(ax, bx, self.prize[0])?.min_cost(3, 1))
+                    .flatten()
+                    .map(|(a, b)| [a, b])
+            }
+            solution => solution.nonnegative_integer().map(|v| [v[0], v[1]]),
+        }
+    }
+
+    pub fn tokens(&self) -> Option<usize> {
+        self.solve().map(|[a, b]| (a * 3 + b) as usize)
     }
 
     pub fn parser<S>(input: &mut S) -> PResult<Self>
@@ -36,31 +51,31 @@ impl System {
             seq!(_: "Prize: ",  Self::prize, _: line_ending),
         )
         .map(|((a,), (b,), (prize,))| Self {
-            vars: Matrix2::from_columns(&[a, b]),
+            vars: [[a[0], b[0]], [a[1], b[1]]],
             prize,
         })
         .parse_next(input)
     }
 
-    pub fn button<S>(input: &mut S) -> PResult<Vector2<f64>>
+    pub fn button<S>(input: &mut S) -> PResult<[i128; 2]>
     where
         for<'a> S: Stream + StreamIsPartial + Compare<&'a str>,
         <S as Stream>::Token: AsChar + Clone,
         <S as Stream>::Slice: AsBStr,
     {
-        seq!(_: "X+", dec_uint::<_, usize, _>, _: ", Y+", dec_uint::<_, usize, _>)
-            .map(|(x, y)| Vector2::new(x as f64, y as f64))
+        seq!(_: "X+", dec_uint::<_, u64, _>, _: ", Y+", dec_uint::<_, u64, _>)
+            .map(|(x, y)| [x.into(), y.into()])
             .parse_next(input)
     }
 
-    pub fn prize<S>(input: &mut S) -> PResult<Vector2<f64>>
+    pub fn prize<S>(input: &mut S) -> PResult<[i128; 2]>
     where
         for<'a> S: Stream + StreamIsPartial + Compare<&'a str>,
         <S as Stream>::Token: AsChar + Clone,
         <S as Stream>::Slice: AsBStr,
     {
-        seq!(_: "X=", dec_uint::<_, usize,_>, _: ", Y=", dec_uint::<_, usize,_>)
-            .map(|(x, y)| Vector2::new(x as f64, y as f64))
+        seq!(_: "X=", dec_uint::<_, u64,_>, _: ", Y=", dec_uint::<_, u64,_>)
+            .map(|(x, y)| [x.into(), y.into()])
             .parse_next(input)
     }
 }
@@ -86,14 +101,7 @@ impl Runner for Day {
     }
 
     fn part1(input: &Self::Input<'_>) -> Result<usize> {
-        Ok(input
-            .iter()
-            .flat_map(|sys| sys.solve())
-            .filter(|s| s.iter().all(|c| (c - c.round()).abs() < 0.001))
-            // .inspect(|s| println!("{s:?}"))
-            .map(|s| s.x.round() as usize * 3 + s.y.round() as usize)
-            // .inspect(|s| println!("{s:?}"))
-            .sum())
+        Ok(input.iter().flat_map(System::tokens).sum())
     }
 
     fn part2(input: &Self::Input<'_>) -> Result<usize> {
@@ -101,13 +109,9 @@ impl Runner for Day {
             .iter()
             .flat_map(|sys| {
                 let mut sys = sys.clone();
-                sys.prize += Vector2::new(10000000000000.0, 10000000000000.0);
-                sys.solve()
+                sys.prize = sys.prize.map(|p| p + 10000000000000);
+                sys.tokens()
             })
-            .filter(|s| s.iter().all(|c| (c - c.round()).abs() < 0.001))
-            // .inspect(|s| println!("{s:?}"))
-            .map(|s| s.x.round() as usize * 3 + s.y.round() as usize)
-            // .inspect(|s| println!("{s:?}"))
             .sum())
     }
 }
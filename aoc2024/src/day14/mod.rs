@@ -1,16 +1,17 @@
 use aoc_utils::{collections::multiset::HashMultiSet, math::coord::Coord};
 use hashbrown::HashSet;
 use miette::Result;
+use ratatui::{crossterm::event::KeyCode, text::Text};
 use rayon::iter::{IntoParallelIterator, ParallelIterator};
 use winnow::{
     ascii::{dec_int, line_ending},
     combinator::{opt, repeat, separated_pair, terminated},
-    seq,
-    stream::{AsBStr, AsChar, Compare, Stream, StreamIsPartial},
-    PResult, Parser,
+    seq, PResult, Parser,
 };
 
 use aoc_utils::errors::ToMiette;
+use aoc_utils::parse::combinators::{labeled_coord, TextStream};
+use aoc_utils::viz::{self, Visualize};
 use aoc_utils::Runner;
 
 pub struct Day;
@@ -24,20 +25,14 @@ pub struct Robot {
 impl Robot {
     pub fn parser<S>(input: &mut S) -> PResult<Self>
     where
-        for<'a> S: Stream + StreamIsPartial + Compare<&'a str>,
-        <S as Stream>::Token: AsChar + Clone,
-        <S as Stream>::Slice: AsBStr,
+        S: TextStream,
     {
         seq!(
-            _: "p=", 
-            separated_pair(dec_int, ",", dec_int), 
-            _: " v=", 
-            separated_pair(dec_int, ",", dec_int),
+            labeled_coord("p"),
+            _: " ",
+            labeled_coord("v"),
             _: opt(line_ending))
-        .map(|(p, v)| Self {
-            position: Coord(p.0, p.1),
-            velocity: Coord(v.0, v.1),
-        })
+        .map(|(position, velocity)| Self { position, velocity })
         .parse_next(input)
     }
 
@@ -91,6 +86,48 @@ impl Field {
     }
 }
 
+/// The robots after some number of seconds, for watching them with `--viz`.
+#[derive(Debug, Clone)]
+pub struct Replay {
+    field: Field,
+    seconds: isize,
+    /// When the tree shows up, worked out before playing so that a failure is reported.
+    tree: isize,
+}
+
+impl Visualize for Replay {
+    fn render(&self) -> Text<'_> {
+        let set = self.field.steps(self.seconds).collect::<HashSet<_>>();
+        Text::from_iter((0..self.field.height).map(|j| {
+            (0..self.field.width)
+                .map(|i| if set.contains(&Coord(i, j)) { '^' } else { ' ' })
+                .collect::<String>()
+        }))
+    }
+
+    fn step(&mut self) -> bool {
+        self.seconds += 1;
+        self.seconds < self.field.width * self.field.height
+    }
+
+    /// `j` jumps straight to the least secure arrangement, where the tree is.
+    fn handle_key(&mut self, key: KeyCode) -> bool {
+        if key != KeyCode::Char('j') {
+            return false;
+        }
+        self.seconds = self.tree;
+        true
+    }
+
+    fn title(&self) -> String {
+        format!(
+            "{} seconds, safety factor {}",
+            self.seconds,
+            self.field.security_factor(self.seconds)
+        )
+    }
+}
+
 impl Runner for Day {
     type Input<'input> = Field;
 
@@ -128,33 +165,17 @@ impl Runner for Day {
             .into_par_iter()
             .map(|i| (i, input.security_factor(i)))
             .min_by_key(|t| t.1);
-        // lowest.iter().for_each(|(i, _)| {
-        //     print_grid(
-        //         *i as usize,
-        //         &input.steps(*i).collect(),
-        //         input.width,
-        //         input.height,
-        //     )
-        // });
         Ok(lowest.unwrap().0 as usize)
     }
-}
 
-#[allow(dead_code)]
-fn print_grid(step: usize, set: &HashSet<Coord>, width: isize, height: isize) {
-    println!("{}", step);
-    println!("{}", unsafe {
-        &vec![b'='; width as usize][..].as_ascii_unchecked().as_str()
-    });
-    for j in 0..height {
-        for i in 0..width {
-            print!("{}", if set.contains(&Coord(i, j)) { "^" } else { " " });
-        }
-        println!();
+    fn visualize(input: &Self::Input<'_>) -> Result<()> {
+        viz::play(Replay {
+            field: input.clone(),
+            seconds: 0,
+            tree: Self::part2(input)? as isize,
+        })?;
+        Ok(())
     }
-    println!("{}", unsafe {
-        &vec![b'='; width as usize][..].as_ascii_unchecked().as_str()
-    });
 }
 
 #[cfg(test)]
@@ -2,15 +2,8 @@ use std::{cell::RefCell, char::ParseCharError, collections::BinaryHeap, rc::Rc,
 
 use aoc_utils::math::coord::Coord;
 use hashbrown::HashMap;
-use miette::{IntoDiagnostic, Result};
-use ratatui::{
-    crossterm::event::{self, Event},
-    layout::{Constraint, Direction, Layout},
-    style::{Color, Style},
-    text::{Line, Span, Text},
-    widgets::Block,
-    DefaultTerminal,
-};
+use miette::Result;
+use ratatui::{crossterm::event::KeyCode, text::Text};
 use winnow::{
     ascii::{line_ending, multispace0},
     combinator::{alt, fail, repeat, terminated},
@@ -21,6 +14,7 @@ use winnow::{
 };
 
 use aoc_utils::errors::ToMiette;
+use aoc_utils::viz::{self, Visualize};
 use aoc_utils::Runner;
 
 pub struct Day;
@@ -153,12 +147,16 @@ pub struct Warehouse {
     height: usize,
     bot: Coord,
     boxes: HashMap<Coord, Object>,
-    instructions: Vec<Dir>,
+    /// Shared, so that the visualizer's copies of every step don't each have their own.
+    instructions: Rc<Vec<Dir>>,
 
     ip: usize,
+    /// The latest log lines, between `MAX_MESSAGES` and twice that once it fills up.
     messages: Vec<String>,
 }
 
+const MAX_MESSAGES: usize = 100;
+
 impl std::fmt::Debug for Warehouse {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("Warehouse")
@@ -198,99 +196,28 @@ impl std::fmt::Debug for Warehouse {
 }
 
 impl Warehouse {
-    pub fn run(&mut self, mut terminal: Option<&mut DefaultTerminal>) -> Result<()> {
-        for _ in 0..self.instructions.len() {
+    pub fn run(&mut self) {
+        while self.ip < self.instructions.len() {
             self.step();
-            if let Some(terminal) = terminal.as_mut() {
-                // let start = Instant::now();
-                loop {
-                    terminal
-                        .draw(|f| {
-                            let msg_percent = 40;
-                            let layout = Layout::default()
-                                .direction(Direction::Vertical)
-                                .margin(1)
-                                .constraints(
-                                    [
-                                        Constraint::Percentage(100 - msg_percent),
-                                        Constraint::Percentage(msg_percent),
-                                    ]
-                                    .as_ref(),
-                                )
-                                .split(f.area());
-                            f.render_widget(self.to_widget(), layout[0]);
-                            f.render_widget(self.message_widget(), layout[1]);
-                        })
-                        .into_diagnostic()?;
-                    if let Event::Key(key_event) = event::read().into_diagnostic()? {
-                        match key_event.code {
-                            event::KeyCode::Char('q') => return Ok(()),
-                            event::KeyCode::Char(' ') => break,
-                            d @ (event::KeyCode::Left
-                            | event::KeyCode::Right
-                            | event::KeyCode::Up
-                            | event::KeyCode::Down) => {
-                                self.instructions.clear();
-                                self.ip = 0;
-                                self.instructions.push(match d {
-                                    event::KeyCode::Left => Dir::Left,
-                                    event::KeyCode::Right => Dir::Right,
-                                    event::KeyCode::Up => Dir::Up,
-                                    event::KeyCode::Down => Dir::Down,
-                                    _ => unreachable!(),
-                                });
-                                break;
-                            }
-                            _ => {}
-                        }
-                    }
-                    // if start.elapsed() > Duration::from_millis(100) {
-                    //     break;
-                    // }
-                }
-            }
         }
-        Ok(())
+    }
+
+    fn log(&mut self, message: String) {
+        if self.messages.len() == 2 * MAX_MESSAGES {
+            self.messages.drain(..MAX_MESSAGES);
+        }
+        self.messages.push(message);
     }
 
     pub fn step(&mut self) {
         let dir = self.instructions[self.ip];
         self.ip += 1;
-        self.messages.push("Stepping".to_string());
+        self.log("Stepping".to_string());
         self.move_object(self.bot, Object::Bot, dir);
     }
 
-    pub fn to_widget(&self) -> ratatui::widgets::Paragraph<'_> {
-        let t = Text::raw(format!("{self:#?}"));
-        ratatui::widgets::Paragraph::new(t)
-    }
-
-    pub fn message_widget(&self) -> ratatui::widgets::Paragraph<'_> {
-        let mut messages = Vec::with_capacity(self.messages.len());
-        messages.extend(
-            self.messages
-                .iter()
-                .enumerate()
-                .skip(isize::max(self.messages.len() as isize - 27, 0) as usize)
-                .map(|(i, l)| {
-                    Line::from(vec![
-                        Span::styled(i.to_string(), Color::White),
-                        Span::raw(" - "),
-                        Span::styled(l.clone(), Color::Green),
-                    ])
-                }),
-        );
-        let t = Text::from(messages);
-        ratatui::widgets::Paragraph::new(t).block(
-            Block::bordered()
-                .title("Messages")
-                .border_style(Style::default().fg(Color::Red)),
-        )
-    }
-
     fn move_object(&mut self, here: Coord, this: Object, dir: Dir) -> bool {
-        self.messages
-            .push(format!("Found  `{this:?}` @ {here:?} going {dir:?}"));
+        self.log(format!("Found  `{this:?}` @ {here:?} going {dir:?}"));
 
         let mut to_move = Default::default();
         let blocked = self.is_blocked(0, here, this, dir, &mut to_move);
@@ -328,17 +255,17 @@ impl Warehouse {
 
         let there = here + dir.to_coord();
         let Some(&that) = self.boxes.get(&there) else {
-            self.messages.push(format!("{spaces}Empty `{there:?}`"));
+            self.log(format!("{spaces}Empty `{there:?}`"));
             return true;
         };
 
         match (dir, that) {
             (_, Object::Wall) => {
-                self.messages.push(format!("{spaces}Blocked `{that:?}`"));
+                self.log(format!("{spaces}Blocked `{that:?}`"));
                 true
             }
             (_, Object::Bot | Object::Space) => {
-                self.messages.push(format!(
+                self.log(format!(
                     "{spaces}Can move: `{this:?}` to {there:?} was `{that:?}`",
                 ));
                 // Clear here
@@ -348,11 +275,10 @@ impl Warehouse {
                 false
             }
             (Dir::Up | Dir::Down, Object::OpenBox | Object::CloseBox) => {
-                self.messages
-                    .push(format!("{spaces}Checking wide box: `{that:?}` {there:?}"));
+                self.log(format!("{spaces}Checking wide box: `{that:?}` {there:?}"));
                 let first_blocked = self.is_blocked(depth + 1, there, that, dir, to_move);
 
-                self.messages.push(format!(
+                self.log(format!(
                     "{spaces}Checking wide box: `{:?}` {:?}",
                     that.opposite(),
                     that.pair_coord(there)
@@ -366,7 +292,7 @@ impl Warehouse {
                 );
                 let blocked = first_blocked || other_blocked;
                 if !(blocked) {
-                    self.messages.push(format!(
+                    self.log(format!(
                         "{spaces}Can move: `{this:?}` from {here:?} to {there:?} and `{:?}` from {:?} to {:?}",
                         this.opposite(),
                         this.pair_coord(here),
@@ -382,7 +308,7 @@ impl Warehouse {
                 blocked
             }
             (_, Object::Box | Object::OpenBox | Object::CloseBox) => {
-                self.messages.push(format!("{spaces}box: `{that:?}`"));
+                self.log(format!("{spaces}box: `{that:?}`"));
                 let blocked = self.is_blocked(depth + 1, there, that, dir, to_move);
                 if !blocked {
                     to_move.push(DepthPair(depth + 1, here, Object::Space));
@@ -402,12 +328,12 @@ impl Warehouse {
         let Some(&that2) = self.boxes.get(&there2) else {
             return false;
         };
-        self.messages.push(format!(
+        self.log(format!(
             "Found [`{that:?}`, `{that2:?}`] at [{there:?},{there2:?}]"
         ));
 
         if that.blocks() || that2.blocks() {
-            self.messages.push("Blocked".to_string());
+            self.log("Blocked".to_string());
             return false;
         }
 
@@ -470,7 +396,7 @@ impl Warehouse {
                 height,
                 bot,
                 boxes,
-                instructions,
+                instructions: Rc::new(instructions),
                 ip: 0,
                 messages: Vec::new(),
             })
@@ -558,6 +484,42 @@ impl Warehouse {
     }
 }
 
+impl Visualize for Warehouse {
+    fn render(&self) -> Text<'_> {
+        Text::raw(format!("{self:#?}"))
+    }
+
+    fn step(&mut self) -> bool {
+        if self.ip == self.instructions.len() {
+            return false;
+        }
+        Warehouse::step(self);
+        true
+    }
+
+    /// The arrow keys push the robot by hand, ahead of the remaining instructions.
+    fn handle_key(&mut self, key: KeyCode) -> bool {
+        let dir = match key {
+            KeyCode::Left => Dir::Left,
+            KeyCode::Right => Dir::Right,
+            KeyCode::Up => Dir::Up,
+            KeyCode::Down => Dir::Down,
+            _ => return false,
+        };
+        Rc::make_mut(&mut self.instructions).insert(self.ip, dir);
+        Warehouse::step(self);
+        true
+    }
+
+    fn messages(&self) -> &[String] {
+        &self.messages
+    }
+
+    fn title(&self) -> String {
+        format!("Warehouse {}/{}", self.ip, self.instructions.len())
+    }
+}
+
 impl Runner for Day {
     type Input<'input> = Warehouse;
 
@@ -566,7 +528,7 @@ impl Runner for Day {
     }
 
     fn comment() -> &'static str {
-        "Run with `cargo run -F day15_ratatui --release -- -d 15` to get a simple UI"
+        "Run with `cargo run --release -- -d 15 --viz` to get a simple UI"
     }
 
     fn get_input(input: &str) -> Result<Self::Input<'_>> {
@@ -575,28 +537,22 @@ impl Runner for Day {
 
     fn part1(input: &Self::Input<'_>) -> Result<usize> {
         let mut warehouse = input.clone();
-        warehouse.run(None)?;
+        warehouse.run();
         log::debug!("Warehouse: {warehouse:?}");
         Ok(warehouse.gps_sum())
     }
 
     fn part2(input: &Self::Input<'_>) -> Result<usize> {
-        #[cfg(feature = "day15_ratatui")]
-        let mut terminal = ratatui::init();
-        #[cfg(feature = "day15_ratatui")]
-        terminal.clear().into_diagnostic()?;
-
-        let warehouse = input.clone();
-        let mut warehouse = warehouse.widen();
-        #[cfg(feature = "day15_ratatui")]
-        warehouse.run(Some(&mut terminal))?;
-        #[cfg(not(feature = "day15_ratatui"))]
-        warehouse.run(None)?;
-
-        #[cfg(feature = "day15_ratatui")]
-        ratatui::restore();
+        let mut warehouse = input.widen();
+        warehouse.run();
         Ok(warehouse.gps_sum())
     }
+
+    fn visualize(input: &Self::Input<'_>) -> Result<()> {
+        let warehouse = viz::play(input.widen())?;
+        println!("GPS sum: {}", warehouse.gps_sum());
+        Ok(())
+    }
 }
 
 #[cfg(test)]
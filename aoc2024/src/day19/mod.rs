@@ -1,6 +1,5 @@
 use std::{borrow::Cow, hash::Hash};
 
-use cached::{proc_macro::cached, Cached};
 use miette::Result;
 use trie_rs::Trie;
 use winnow::{
@@ -11,6 +10,7 @@ use winnow::{
     PResult, Parser,
 };
 
+use aoc_utils::collections::Memo;
 use aoc_utils::errors::ToMiette;
 use aoc_utils::Runner;
 
@@ -104,54 +104,60 @@ impl<'input> Onsen<'input> {
     }
 }
 
-#[cached(key = "String", convert = r#"{ goal.to_string() }"#)]
-pub fn is_match(onsen: &Onsen<'_>, goal: &str) -> bool {
+pub fn is_match<'a>(onsen: &Onsen<'_>, goal: &'a str, memo: &mut Memo<&'a str, bool>) -> bool {
     if goal.is_empty() {
         return true;
     }
 
-    log::debug!("Searching for goal: {goal}");
+    memo.memoize(goal, |memo| {
+        log::debug!("Searching for goal: {goal}");
 
-    if onsen.trie.exact_match(goal) {
-        log::debug!("Found exact match: {goal}");
-        return true;
-    }
+        if onsen.trie.exact_match(goal) {
+            log::debug!("Found exact match: {goal}");
+            return true;
+        }
 
-    let prefixes = onsen.trie.common_prefix_search(goal);
-    prefixes
-        .inspect(|prefix| {
-            log::debug!("common: {prefix}");
-            log::debug!("goal: {goal}");
-        })
-        .any(|prefix: String| {
-            let left = &goal[prefix.len()..];
-            is_match(onsen, left)
-        })
+        let prefixes = onsen.trie.common_prefix_search(goal);
+        prefixes
+            .inspect(|prefix| {
+                log::debug!("common: {prefix}");
+                log::debug!("goal: {goal}");
+            })
+            .any(|prefix: String| {
+                let left = &goal[prefix.len()..];
+                is_match(onsen, left, memo)
+            })
+    })
 }
 
-#[cached(key = "String", convert = r#"{ goal.to_string() }"#)]
-pub fn count_matches(onsen: &Onsen<'_>, goal: &str /* , shift: usize */) -> usize {
+pub fn count_matches<'a>(
+    onsen: &Onsen<'_>,
+    goal: &'a str,
+    memo: &mut Memo<&'a str, usize>, /* , shift: usize */
+) -> usize {
     // let spaces = String::from_iter(vec![' '; shift]);
     let spaces = String::new();
     if goal.is_empty() {
         return 1;
     }
 
-    log::debug!("{spaces}Searching for goal: {goal}");
-
-    let prefixes = onsen.trie.common_prefix_search(goal);
-    log::debug!("{spaces}prefixes: {:?}", Vec::from_iter(prefixes.clone()));
-    prefixes
-        .map(|prefix: String| {
-            log::debug!("{spaces}common: {prefix}");
-            log::debug!("{spaces}goal: {goal}");
-            let left = &goal[prefix.len()..];
-            count_matches(onsen, left)
-        })
-        .inspect(|count| {
-            log::debug!("{spaces}count: {count}");
-        })
-        .sum()
+    memo.memoize(goal, |memo| {
+        log::debug!("{spaces}Searching for goal: {goal}");
+
+        let prefixes = onsen.trie.common_prefix_search(goal);
+        log::debug!("{spaces}prefixes: {:?}", Vec::from_iter(prefixes.clone()));
+        prefixes
+            .map(|prefix: String| {
+                log::debug!("{spaces}common: {prefix}");
+                log::debug!("{spaces}goal: {goal}");
+                let left = &goal[prefix.len()..];
+                count_matches(onsen, left, memo)
+            })
+            .inspect(|count| {
+                log::debug!("{spaces}count: {count}");
+            })
+            .sum()
+    })
 }
 
 impl Runner for Day {
@@ -167,22 +173,20 @@ impl Runner for Day {
     }
 
     fn part1(input: &Self::Input<'_>) -> Result<usize> {
-        // Clear cache so we aren't cheating in our benchmarks
-        IS_MATCH.lock().unwrap().cache_clear();
+        let mut memo = Memo::new();
         Ok(input
             .goals
             .iter()
-            .filter(|goal| is_match(input, goal))
+            .filter(|goal| is_match(input, goal, &mut memo))
             .count())
     }
 
     fn part2(input: &Self::Input<'_>) -> Result<usize> {
-        // Clear cache so we aren't cheating in our benchmarks
-        COUNT_MATCHES.lock().unwrap().cache_clear();
+        let mut memo = Memo::new();
         Ok(input
             .goals
             .iter()
-            .map(|goal| count_matches(input, goal))
+            .map(|goal| count_matches(input, goal, &mut memo))
             .sum())
     }
 }
@@ -4,8 +4,7 @@ use std::{
     ops::Deref,
 };
 
-use aoc_utils::{graph::four_neighbors, math::coord::Coord, Runner};
-use hashbrown::HashMap;
+use aoc_utils::{collections::Memo, graph::four_neighbors, math::coord::Coord, Runner};
 use itertools::Itertools;
 use miette::Result;
 use pathfinding::directed::astar::astar_bag;
@@ -237,7 +236,7 @@ impl Path {
 
 fn shortest_path<const N: usize>(
     code: &[NumberButton],
-    cache: &mut HashMap<(usize, Path), usize>,
+    cache: &mut Memo<(usize, Path), usize>,
 ) -> usize {
     once(NumberButton::A)
         .chain(code.iter().copied())
@@ -256,34 +255,30 @@ fn shortest_path<const N: usize>(
 fn shortest_path_arrow<const N: usize>(
     code: &[ArrowButton],
     depth: usize,
-    cache: &mut HashMap<(usize, Path), usize>,
+    cache: &mut Memo<(usize, Path), usize>,
 ) -> usize {
-    if let Some(&cached) = cache.get(&(depth, Path::from_iter(code))) {
-        return cached;
-    }
-
     if depth > N {
         return code.len();
     }
 
-    let sum = once(ArrowButton::A)
-        .chain(code.iter().copied())
-        .tuple_windows()
-        .map(|(a, b)| {
-            let paths = a.paths(b);
-            if depth == N {
-                paths.into_iter().map(|path| path.len()).min().unwrap()
-            } else {
-                paths
-                    .into_iter()
-                    .map(|path| shortest_path_arrow::<N>(&path, depth + 1, cache))
-                    .min()
-                    .unwrap()
-            }
-        })
-        .sum::<usize>();
-    cache.insert((depth, Path::from_iter(code)), sum);
-    sum
+    cache.memoize((depth, Path::from_iter(code)), |cache| {
+        once(ArrowButton::A)
+            .chain(code.iter().copied())
+            .tuple_windows()
+            .map(|(a, b)| {
+                let paths = a.paths(b);
+                if depth == N {
+                    paths.into_iter().map(|path| path.len()).min().unwrap()
+                } else {
+                    paths
+                        .into_iter()
+                        .map(|path| shortest_path_arrow::<N>(&path, depth + 1, cache))
+                        .min()
+                        .unwrap()
+                }
+            })
+            .sum::<usize>()
+    })
 }
 
 fn value(buttons: &[NumberButton]) -> usize {
@@ -316,7 +311,7 @@ impl Runner for Day {
     }
 
     fn part1(input: &Self::Input<'_>) -> Result<usize> {
-        let mut cache = HashMap::new();
+        let mut cache = Memo::new();
         Ok(input
             .iter()
             .map(|combo| shortest_path::<2>(combo, &mut cache) * value(combo))
@@ -324,7 +319,7 @@ impl Runner for Day {
     }
 
     fn part2(input: &Self::Input<'_>) -> Result<usize> {
-        let mut cache = HashMap::new();
+        let mut cache = Memo::new();
         Ok(input
             .iter()
             .map(|combo| shortest_path::<25>(combo, &mut cache) * value(combo))
@@ -430,7 +425,7 @@ mod tests {
     fn combo_029a() {
         let _ = env_logger::try_init();
 
-        let mut cache = HashMap::new();
+        let mut cache = Memo::new();
         let shortest = shortest_path::<0>(
             &[
                 NumberButton::N(0),
@@ -448,7 +443,7 @@ mod tests {
     fn one_robot_029a() {
         let _ = env_logger::try_init();
 
-        let mut cache = HashMap::new();
+        let mut cache = Memo::new();
         let shortest = shortest_path::<1>(
             &[
                 NumberButton::N(0),
@@ -467,7 +462,7 @@ mod tests {
     fn two_robot_029a() {
         let _ = env_logger::try_init();
 
-        let mut cache = HashMap::new();
+        let mut cache = Memo::new();
         let shortest = shortest_path::<2>(
             &[
                 NumberButton::N(0),
@@ -484,7 +479,7 @@ mod tests {
     fn two_robot_379a() {
         let _ = env_logger::try_init();
 
-        let mut cache = HashMap::new();
+        let mut cache = Memo::new();
         let shortest = shortest_path::<2>(
             &[
                 NumberButton::N(3),
@@ -1,7 +1,6 @@
 #![feature(associated_type_defaults)]
 #![feature(binary_heap_into_iter_sorted)]
 #![feature(debug_closure_helpers)]
-#![feature(ascii_char)]
 #![feature(impl_trait_in_assoc_type)]
 #![warn(clippy::all)]
 //#![warn(clippy::pedantic)]
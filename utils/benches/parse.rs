@@ -0,0 +1,50 @@
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+
+use aoc_utils::parse::{ints, parse_int, parse_uint, try_parse_int, try_parse_uint};
+
+const INPUTS: [&str; 4] = ["7", "12345678", "1234567890123456", "12345678901234567890"];
+
+fn uint(c: &mut Criterion) {
+    let mut group = c.benchmark_group("parse_uint");
+    for input in INPUTS.iter().filter(|i| i.parse::<u64>().is_ok()) {
+        group.bench_with_input(BenchmarkId::new("unchecked", input.len()), input, |b, i| {
+            b.iter(|| parse_uint::<usize>(black_box(i)))
+        });
+        group.bench_with_input(BenchmarkId::new("checked", input.len()), input, |b, i| {
+            b.iter(|| try_parse_uint::<u64>(black_box(i)))
+        });
+        group.bench_with_input(BenchmarkId::new("std", input.len()), input, |b, i| {
+            b.iter(|| black_box(i).parse::<u64>())
+        });
+    }
+    group.finish();
+}
+
+fn int(c: &mut Criterion) {
+    let mut group = c.benchmark_group("parse_int");
+    for input in INPUTS
+        .iter()
+        .map(|i| format!("-{i}"))
+        .filter(|i| i.parse::<i64>().is_ok())
+    {
+        group.bench_with_input(
+            BenchmarkId::new("unchecked", input.len()),
+            &input,
+            |b, i| b.iter(|| parse_int::<isize>(black_box(i))),
+        );
+        group.bench_with_input(BenchmarkId::new("checked", input.len()), &input, |b, i| {
+            b.iter(|| try_parse_int::<i64>(black_box(i)))
+        });
+    }
+    group.finish();
+}
+
+fn all_ints(c: &mut Criterion) {
+    let line = b"p=12,-34 v=5678,-9 x=123456789012 y=-42";
+    c.bench_function("ints", |b| {
+        b.iter(|| ints::<i64>(black_box(line)).sum::<Result<i64, _>>())
+    });
+}
+
+criterion_group!(benches, uint, int, all_ints);
+criterion_main!(benches);
@@ -0,0 +1,192 @@
+//! Puzzle answers in the form they're submitted in.
+//!
+//! Solutions return whatever type is natural, as long as it implements [`ToAnswer`]; the runner
+//! turns it into an [`Answer`] and prints that, so a `String` answer shows up without quotes and
+//! ASCII art starts on its own line.
+use std::{
+    fmt::{self, Display},
+    str::FromStr,
+};
+
+/// A canonical answer: two answers are equal exactly when they'd be submitted the same way.
+///
+/// Build text with [`Answer::text`] rather than [`Answer::Text`], so that digits end up as a
+/// [`Answer::Number`] whichever way they were produced.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Answer {
+    Number(i128),
+    /// A single line, without surrounding whitespace, that isn't a number as it would be printed.
+    /// Zero-padded digits like `007` stay text, since they're submitted with their zeros.
+    Text(String),
+    /// Letters drawn over several lines, with trailing whitespace cut off every line.
+    Art(String),
+}
+
+impl Answer {
+    /// Trimmed text, or a [`Answer::Number`] if it's exactly how that number prints.
+    pub fn text(text: impl AsRef<str>) -> Self {
+        let text = text.as_ref().trim();
+        match text.parse::<i128>() {
+            Ok(n) if n.to_string() == text => Self::Number(n),
+            _ => Self::Text(text.to_string()),
+        }
+    }
+
+    /// Art from its rows, dropping blank rows at the top and bottom.
+    pub fn art<I, S>(rows: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let rows = rows
+            .into_iter()
+            .map(|row| row.as_ref().trim_end().to_string())
+            .collect::<Vec<_>>();
+        let first = rows.iter().position(|row| !row.is_empty()).unwrap_or(0);
+        let last = rows
+            .iter()
+            .rposition(|row| !row.is_empty())
+            .map_or(0, |i| i + 1);
+        Self::Art(rows[first..last.max(first)].join("\n"))
+    }
+
+    /// Art from a grid of lit pixels, drawn with `#` and `.`.
+    pub fn pixels<I, R>(rows: I) -> Self
+    where
+        I: IntoIterator<Item = R>,
+        R: IntoIterator<Item = bool>,
+    {
+        Self::art(rows.into_iter().map(|row| {
+            row.into_iter()
+                .map(|lit| if lit { '#' } else { '.' })
+                .collect::<String>()
+        }))
+    }
+
+    pub fn is_multiline(&self) -> bool {
+        matches!(self, Self::Art(_))
+    }
+}
+
+/// The submission form: digits, the text, or the art's rows joined with newlines.
+impl Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Number(n) => write!(f, "{n}"),
+            Self::Text(text) | Self::Art(text) => write!(f, "{text}"),
+        }
+    }
+}
+
+/// Reads back the submission form, e.g. from a cache, the same way [`ToAnswer`] reads a `&str`.
+impl FromStr for Answer {
+    type Err = std::convert::Infallible;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(s.to_answer())
+    }
+}
+
+/// A value that can be turned into an [`Answer`].
+pub trait ToAnswer {
+    fn to_answer(&self) -> Answer;
+}
+
+impl ToAnswer for Answer {
+    fn to_answer(&self) -> Answer {
+        self.clone()
+    }
+}
+
+macro_rules! int_answer {
+    ($($t:ty),*) => {
+        $(impl ToAnswer for $t {
+            fn to_answer(&self) -> Answer {
+                i128::try_from(*self).map_or_else(|_| Answer::text(self.to_string()), Answer::Number)
+            }
+        })*
+    };
+}
+
+int_answer!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize);
+
+impl ToAnswer for String {
+    fn to_answer(&self) -> Answer {
+        self.as_str().to_answer()
+    }
+}
+
+/// Text with more than one line is taken as art, and digits as a number.
+impl ToAnswer for &str {
+    fn to_answer(&self) -> Answer {
+        if self.trim().contains('\n') {
+            Answer::art(self.lines())
+        } else {
+            Answer::text(self)
+        }
+    }
+}
+
+impl ToAnswer for char {
+    fn to_answer(&self) -> Answer {
+        Answer::text(self.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn canonical() {
+        assert_eq!(42usize.to_answer(), Answer::Number(42));
+        assert_eq!((-7i64).to_answer().to_string(), "-7");
+        assert_eq!(u128::MAX.to_answer(), Answer::Text(u128::MAX.to_string()));
+        assert_eq!("co,de,ka,ta".to_answer().to_string(), "co,de,ka,ta");
+        assert_eq!(String::from(" 4,6,3 \n").to_answer(), Answer::text("4,6,3"));
+
+        let art = Answer::pixels([
+            [true, false, true],
+            [false, false, false],
+            [true, true, false],
+        ]);
+        assert_eq!(art.to_string(), "#.#\n...\n##.");
+        assert!(art.is_multiline());
+        assert_eq!("\n#.#  \n...\n##.\n\n".to_answer(), art);
+    }
+
+    #[test]
+    fn parse() {
+        assert_eq!("1068781\n".parse(), Ok(Answer::Number(1068781)));
+        assert_eq!("abc".parse(), Ok(Answer::text("abc")));
+        let art = Answer::art(["#..#", "####"]);
+        assert_eq!(art.to_string().parse(), Ok(art));
+    }
+
+    #[test]
+    fn digits_are_numbers() {
+        assert_eq!("123".to_answer(), 123usize.to_answer());
+        assert_eq!(String::from("-5").to_answer(), Answer::Number(-5));
+        assert_eq!('7'.to_answer(), Answer::Number(7));
+        assert_eq!("007".to_answer(), Answer::Text("007".to_string()));
+        assert_eq!("007".parse(), Ok(Answer::Text("007".to_string())));
+        assert_eq!("+5".to_answer().to_string(), "+5");
+    }
+
+    #[test]
+    fn round_trips() {
+        let answers = [
+            42usize.to_answer(),
+            (-7i64).to_answer(),
+            u128::MAX.to_answer(),
+            "co,de,ka,ta".to_answer(),
+            "007".to_answer(),
+            String::from("123").to_answer(),
+            'x'.to_answer(),
+            "#..#\n####\n".to_answer(),
+        ];
+        for answer in answers {
+            assert_eq!(answer.to_string().parse(), Ok(answer.clone()), "{answer:?}");
+        }
+    }
+}
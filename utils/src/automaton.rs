@@ -0,0 +1,57 @@
+//! Cellular automata: a double-buffered [`Dense`] board for fixed grids, and a [`Sparse`] board
+//! of live cells for boards that grow forever.
+pub mod dense;
+pub mod sparse;
+
+pub use dense::{Dense, Neighborhood};
+pub use sparse::Sparse;
+
+/// Which cells count as touching.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Adjacency {
+    /// Cells that differ by one along a single axis: 4 in 2D, 6 in 3D.
+    VonNeumann,
+    /// Every cell in the surrounding cube: 8 in 2D, 26 in 3D.
+    Moore,
+}
+
+impl Adjacency {
+    /// The offsets of every neighbor of the origin in `N` dimensions.
+    pub fn offsets<const N: usize>(self) -> Vec<[i64; N]> {
+        match self {
+            Self::VonNeumann => (0..N)
+                .flat_map(|axis| {
+                    [-1, 1].map(|d| {
+                        let mut offset = [0; N];
+                        offset[axis] = d;
+                        offset
+                    })
+                })
+                .collect(),
+            Self::Moore => (0..3usize.pow(N as u32))
+                .map(|mut i| {
+                    [0; N].map(|_| {
+                        let d = (i % 3) as i64 - 1;
+                        i /= 3;
+                        d
+                    })
+                })
+                .filter(|offset| offset.iter().any(|&d| d != 0))
+                .collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn offsets() {
+        assert_eq!(Adjacency::VonNeumann.offsets::<2>().len(), 4);
+        assert_eq!(Adjacency::Moore.offsets::<2>().len(), 8);
+        assert_eq!(Adjacency::VonNeumann.offsets::<3>().len(), 6);
+        assert_eq!(Adjacency::Moore.offsets::<4>().len(), 80);
+        assert!(!Adjacency::Moore.offsets::<3>().contains(&[0, 0, 0]));
+    }
+}
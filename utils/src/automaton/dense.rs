@@ -0,0 +1,232 @@
+use ndarray::Array2;
+use rayon::prelude::*;
+
+use super::Adjacency;
+use crate::collections::HVec;
+
+/// How a [`Dense`] board finds the neighbors of a cell.
+#[derive(Debug, Clone, Copy)]
+pub enum Neighborhood<T> {
+    Adjacent(Adjacency),
+    /// The first cell in each direction that the function says can't be seen through.
+    ///
+    /// Whether a cell can be seen through is decided once, on the initial board.
+    LineOfSight(Adjacency, fn(&T) -> bool),
+}
+
+/// A fixed-size 2D board that is updated all at once, writing each generation into a second
+/// buffer and swapping.
+#[derive(Debug, Clone)]
+pub struct Dense<T> {
+    cells: Array2<T>,
+    next: Vec<T>,
+    neighbors: Vec<HVec<usize, 8>>,
+    generation: usize,
+}
+
+/// The neighbors of one cell, as handed to a rule.
+#[derive(Clone)]
+pub struct Neighbors<'a, T> {
+    cells: &'a [T],
+    indices: std::slice::Iter<'a, usize>,
+}
+
+impl<'a, T> Iterator for Neighbors<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.indices.next().map(|&i| &self.cells[i])
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.indices.size_hint()
+    }
+}
+
+impl<T> ExactSizeIterator for Neighbors<'_, T> {}
+
+impl<T> Dense<T>
+where
+    T: Clone + PartialEq,
+{
+    pub fn new(cells: Array2<T>, neighborhood: Neighborhood<T>) -> Self {
+        let cells = cells.as_standard_layout().into_owned();
+        let (rows, cols) = cells.dim();
+        let (adjacency, transparent) = match neighborhood {
+            Neighborhood::Adjacent(adjacency) => (adjacency, None),
+            Neighborhood::LineOfSight(adjacency, transparent) => (adjacency, Some(transparent)),
+        };
+        let offsets = adjacency.offsets::<2>();
+        let inside = |r: i64, c: i64| {
+            ((0..rows as i64).contains(&r) && (0..cols as i64).contains(&c))
+                .then_some((r as usize, c as usize))
+        };
+        let neighbors = cells
+            .indexed_iter()
+            .map(|((r, c), _)| {
+                offsets
+                    .iter()
+                    .filter_map(|&[dr, dc]| {
+                        let mut at = inside(r as i64 + dr, c as i64 + dc)?;
+                        while let Some(transparent) = transparent {
+                            if !transparent(&cells[at]) {
+                                break;
+                            }
+                            at = inside(at.0 as i64 + dr, at.1 as i64 + dc)?;
+                        }
+                        Some(at.0 * cols + at.1)
+                    })
+                    .collect()
+            })
+            .collect();
+        let next = cells.iter().cloned().collect();
+        Self {
+            cells,
+            next,
+            neighbors,
+            generation: 0,
+        }
+    }
+
+    pub fn cells(&self) -> &Array2<T> {
+        &self.cells
+    }
+
+    pub fn into_cells(self) -> Array2<T> {
+        self.cells
+    }
+
+    /// How many steps have been taken.
+    pub fn generation(&self) -> usize {
+        self.generation
+    }
+
+    fn neighbors_of(&self, index: usize) -> Neighbors<'_, T> {
+        Neighbors {
+            cells: self.cells.as_slice().unwrap(),
+            indices: self.neighbors[index].iter(),
+        }
+    }
+
+    fn swap(&mut self) -> bool {
+        let current = self.cells.as_slice_mut().unwrap();
+        let changed = current != self.next.as_slice();
+        current.swap_with_slice(&mut self.next);
+        self.generation += 1;
+        changed
+    }
+
+    /// Applies `rule` to every cell at once. Returns whether anything changed.
+    pub fn step<F>(&mut self, rule: F) -> bool
+    where
+        F: Fn(&T, Neighbors<'_, T>) -> T,
+    {
+        let mut next = std::mem::take(&mut self.next);
+        let cells = self.cells.as_slice().unwrap();
+        next.iter_mut()
+            .zip(cells)
+            .enumerate()
+            .for_each(|(i, (slot, cell))| *slot = rule(cell, self.neighbors_of(i)));
+        self.next = next;
+        self.swap()
+    }
+
+    /// [`Dense::step`], spread over the rayon thread pool.
+    pub fn par_step<F>(&mut self, rule: F) -> bool
+    where
+        T: Send + Sync,
+        F: Fn(&T, Neighbors<'_, T>) -> T + Sync,
+    {
+        let mut next = std::mem::take(&mut self.next);
+        let cells = self.cells.as_slice().unwrap();
+        next.par_iter_mut()
+            .zip(cells)
+            .enumerate()
+            .for_each(|(i, (slot, cell))| *slot = rule(cell, self.neighbors_of(i)));
+        self.next = next;
+        self.swap()
+    }
+
+    /// Steps until a generation is the same as the one before it, and returns the number of steps
+    /// that changed something.
+    pub fn run_until_stable<F>(&mut self, rule: F) -> usize
+    where
+        F: Fn(&T, Neighbors<'_, T>) -> T,
+    {
+        let start = self.generation;
+        while self.step(&rule) {}
+        self.generation - start - 1
+    }
+
+    /// [`Dense::run_until_stable`] with [`Dense::par_step`].
+    pub fn par_run_until_stable<F>(&mut self, rule: F) -> usize
+    where
+        T: Send + Sync,
+        F: Fn(&T, Neighbors<'_, T>) -> T + Sync,
+    {
+        let start = self.generation;
+        while self.par_step(&rule) {}
+        self.generation - start - 1
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 2020 day 11
+    const SEATS: &str = "\
+        L.LL.LL.LL\n\
+        LLLLLLL.LL\n\
+        L.L.L..L..\n\
+        LLLL.LL.LL\n\
+        L.LL.LL.LL\n\
+        L.LLLLL.LL\n\
+        ..L.L.....\n\
+        LLLLLLLLLL\n\
+        L.LLLLLL.L\n\
+        L.LLLLL.LL\n\
+    ";
+
+    fn seats() -> Array2<char> {
+        let rows = SEATS
+            .lines()
+            .map(|l| l.chars().collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+        Array2::from_shape_vec((rows.len(), rows[0].len()), rows.concat()).unwrap()
+    }
+
+    fn rule(crowded: usize) -> impl Fn(&char, Neighbors<'_, char>) -> char + Sync {
+        move |&cell, neighbors| {
+            let occupied = neighbors.filter(|&&n| n == '#').count();
+            match cell {
+                'L' if occupied == 0 => '#',
+                '#' if occupied >= crowded => 'L',
+                _ => cell,
+            }
+        }
+    }
+
+    fn occupied(board: &Dense<char>) -> usize {
+        board.cells().iter().filter(|&&c| c == '#').count()
+    }
+
+    #[test]
+    fn adjacent_seats() {
+        let mut board = Dense::new(seats(), Neighborhood::Adjacent(Adjacency::Moore));
+        assert!(board.step(rule(4)));
+        assert_eq!(occupied(&board), 71);
+        assert_eq!(board.run_until_stable(rule(4)), 4);
+        assert_eq!(occupied(&board), 37);
+        assert!(!board.step(rule(4)));
+    }
+
+    #[test]
+    fn visible_seats() {
+        let neighborhood = Neighborhood::LineOfSight(Adjacency::Moore, |&c| c == '.');
+        let mut board = Dense::new(seats(), neighborhood);
+        board.par_run_until_stable(rule(5));
+        assert_eq!(occupied(&board), 26);
+        assert_eq!(board.generation(), 7);
+    }
+}
@@ -0,0 +1,194 @@
+use hashbrown::{HashMap, HashSet};
+use rayon::prelude::*;
+
+use super::Adjacency;
+
+/// An unbounded board in `N` dimensions that only remembers its live cells.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Sparse<const N: usize> {
+    live: HashSet<[i64; N]>,
+    offsets: Vec<[i64; N]>,
+    generation: usize,
+}
+
+fn add<const N: usize>(a: [i64; N], b: [i64; N]) -> [i64; N] {
+    std::array::from_fn(|i| a[i] + b[i])
+}
+
+impl<const N: usize> Sparse<N> {
+    pub fn new<I>(live: I, adjacency: Adjacency) -> Self
+    where
+        I: IntoIterator<Item = [i64; N]>,
+    {
+        Self {
+            live: live.into_iter().collect(),
+            offsets: adjacency.offsets(),
+            generation: 0,
+        }
+    }
+
+    pub fn live(&self) -> &HashSet<[i64; N]> {
+        &self.live
+    }
+
+    pub fn len(&self) -> usize {
+        self.live.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.live.is_empty()
+    }
+
+    pub fn contains(&self, cell: &[i64; N]) -> bool {
+        self.live.contains(cell)
+    }
+
+    pub fn generation(&self) -> usize {
+        self.generation
+    }
+
+    /// The smallest and largest coordinate along each axis, if anything is alive.
+    pub fn bounds(&self) -> Option<([i64; N], [i64; N])> {
+        let first = *self.live.iter().next()?;
+        Some(self.live.iter().fold((first, first), |(lo, hi), cell| {
+            (
+                std::array::from_fn(|i| lo[i].min(cell[i])),
+                std::array::from_fn(|i| hi[i].max(cell[i])),
+            )
+        }))
+    }
+
+    fn count_into(&self, counts: &mut HashMap<[i64; N], usize>, cell: [i64; N]) {
+        for &offset in &self.offsets {
+            *counts.entry(add(cell, offset)).or_default() += 1;
+        }
+    }
+
+    fn apply<F>(&mut self, counts: HashMap<[i64; N], usize>, rule: F) -> bool
+    where
+        F: Fn(bool, usize) -> bool,
+    {
+        // Live cells without a single live neighbor never show up in the counts.
+        let isolated = if rule(true, 0) {
+            self.live
+                .iter()
+                .filter(|cell| !counts.contains_key(*cell))
+                .copied()
+                .collect()
+        } else {
+            Vec::new()
+        };
+        let mut next = counts
+            .into_iter()
+            .filter(|(cell, n)| rule(self.live.contains(cell), *n))
+            .map(|(cell, _)| cell)
+            .collect::<HashSet<_>>();
+        next.extend(isolated);
+        let changed = next != self.live;
+        self.live = next;
+        self.generation += 1;
+        changed
+    }
+
+    /// Moves to the next generation. `rule` is called with whether a cell is alive and how many
+    /// live neighbors it has, and says whether it's alive next; dead cells with no live neighbors
+    /// stay dead. Returns whether anything changed.
+    pub fn step<F>(&mut self, rule: F) -> bool
+    where
+        F: Fn(bool, usize) -> bool,
+    {
+        let mut counts = HashMap::with_capacity(self.live.len() * self.offsets.len());
+        for &cell in &self.live {
+            self.count_into(&mut counts, cell);
+        }
+        self.apply(counts, rule)
+    }
+
+    /// [`Sparse::step`], counting neighbors on the rayon thread pool.
+    pub fn par_step<F>(&mut self, rule: F) -> bool
+    where
+        F: Fn(bool, usize) -> bool,
+    {
+        let counts = self
+            .live
+            .par_iter()
+            .fold(HashMap::new, |mut counts, &cell| {
+                self.count_into(&mut counts, cell);
+                counts
+            })
+            .reduce(HashMap::new, |mut a, b| {
+                for (cell, n) in b {
+                    *a.entry(cell).or_default() += n;
+                }
+                a
+            });
+        self.apply(counts, rule)
+    }
+
+    /// Steps until nothing changes, or `limit` steps have been taken. Returns whether the board
+    /// settled.
+    pub fn run_until_stable<F>(&mut self, limit: usize, rule: F) -> bool
+    where
+        F: Fn(bool, usize) -> bool,
+    {
+        (0..limit).any(|_| !self.step(&rule))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn life(alive: bool, n: usize) -> bool {
+        matches!((alive, n), (true, 2) | (_, 3))
+    }
+
+    // 2020 day 17
+    fn glider<const N: usize>() -> Sparse<N> {
+        let live = ".#.\n..#\n###"
+            .lines()
+            .enumerate()
+            .flat_map(|(y, line)| {
+                line.char_indices()
+                    .filter(|&(_, c)| c == '#')
+                    .map(move |(x, _)| {
+                        let mut cell = [0; N];
+                        cell[0] = x as i64;
+                        cell[1] = y as i64;
+                        cell
+                    })
+            })
+            .collect::<Vec<_>>();
+        Sparse::new(live, Adjacency::Moore)
+    }
+
+    #[test]
+    fn conway_cubes() {
+        let mut board = glider::<3>();
+        assert!(board.step(life));
+        assert_eq!(board.len(), 11);
+        (1..6).for_each(|_| {
+            board.step(life);
+        });
+        assert_eq!(board.len(), 112);
+        assert_eq!(board.generation(), 6);
+
+        let mut board = glider::<4>();
+        (0..6).for_each(|_| {
+            board.par_step(life);
+        });
+        assert_eq!(board.len(), 848);
+    }
+
+    #[test]
+    fn stable() {
+        let block = [[0, 0], [0, 1], [1, 0], [1, 1]];
+        let mut board = Sparse::new(block, Adjacency::Moore);
+        assert!(board.run_until_stable(10, life));
+        assert_eq!(board.bounds(), Some(([0, 0], [1, 1])));
+
+        let mut board = glider::<2>();
+        assert!(!board.run_until_stable(10, life));
+        assert_eq!(board.len(), 5);
+    }
+}
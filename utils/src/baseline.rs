@@ -0,0 +1,332 @@
+//! Stage timings saved per machine and git revision, so a change can be checked for slowdowns.
+//!
+//! A year binary writes one with `--save-baseline`, into
+//! `baselines/<year>/<machine>/<revision>.tsv` next to the `.env` file, and `compare` reads two of
+//! them back:
+//!
+//! ```sh
+//! cargo run --release -- --save-baseline
+//! cargo run --release -- compare main --threshold 10
+//! ```
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt::{self, Display},
+    path::PathBuf,
+    process::Command,
+    str::FromStr,
+    time::Duration,
+};
+
+use miette::{IntoDiagnostic, Result, WrapErr};
+
+use crate::{errors::Error, utils::file::project_root, Stage, StageTime};
+
+/// How long each stage of each day took, on one machine at one revision.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Baseline {
+    times: BTreeMap<(usize, Stage), Duration>,
+}
+
+impl Baseline {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn from_times<'a>(times: impl IntoIterator<Item = &'a StageTime>) -> Self {
+        let mut baseline = Self::new();
+        for time in times {
+            baseline.insert(time.day(), time.stage(), time.time);
+        }
+        baseline
+    }
+
+    pub fn insert(&mut self, day: usize, stage: Stage, time: Duration) {
+        self.times.insert((day, stage), time);
+    }
+
+    pub fn get(&self, day: usize, stage: Stage) -> Option<Duration> {
+        self.times.get(&(day, stage)).copied()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (usize, Stage, Duration)> + '_ {
+        self.times
+            .iter()
+            .map(|(&(day, stage), &time)| (day, stage, time))
+    }
+
+    pub fn len(&self) -> usize {
+        self.times.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.times.is_empty()
+    }
+
+    pub fn path(year: usize, machine: &str, revision: &str) -> Result<PathBuf> {
+        Ok(project_root()?
+            .join("baselines")
+            .join(year.to_string())
+            .join(machine)
+            .join(format!("{revision}.tsv")))
+    }
+
+    /// Writes the baseline for the current machine, replacing one saved earlier at `revision`.
+    pub fn save(&self, year: usize, revision: &str) -> Result<PathBuf> {
+        let path = Self::path(year, &machine(), revision)?;
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).into_diagnostic()?;
+        }
+        std::fs::write(&path, self.to_string())
+            .into_diagnostic()
+            .wrap_err_with(|| format!("failed to write {}", path.display()))?;
+        Ok(path)
+    }
+
+    /// Reads the baseline this machine saved at `revision`.
+    pub fn load(year: usize, revision: &str) -> Result<Self> {
+        let path = Self::path(year, &machine(), revision)?;
+        let text = std::fs::read_to_string(&path)
+            .into_diagnostic()
+            .wrap_err_with(|| format!("no baseline for {revision} at {}", path.display()))?;
+        text.parse()
+            .into_diagnostic()
+            .wrap_err_with(|| format!("reading {}", path.display()))
+    }
+}
+
+/// One `day<TAB>stage<TAB>nanoseconds` line per stage.
+impl Display for Baseline {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (day, stage, time) in self.iter() {
+            writeln!(f, "{day}\t{}\t{}", stage.name(), time.as_nanos())?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for Baseline {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut baseline = Self::new();
+        for line in s.lines().filter(|l| !l.trim().is_empty()) {
+            let bad = || Error::InvalidInput(format!("bad baseline line `{line}`"));
+            let mut fields = line.split('\t');
+            let (Some(day), Some(stage), Some(nanos), None) =
+                (fields.next(), fields.next(), fields.next(), fields.next())
+            else {
+                return Err(bad());
+            };
+            let day = day.parse().map_err(|_| bad())?;
+            let stage = stage.parse()?;
+            let nanos: u64 = nanos.parse().map_err(|_| bad())?;
+            baseline.insert(day, stage, Duration::from_nanos(nanos));
+        }
+        Ok(baseline)
+    }
+}
+
+/// The machine baselines are filed under: `AOC_MACHINE` if set, otherwise the hostname.
+pub fn machine() -> String {
+    std::env::var("AOC_MACHINE")
+        .or_else(|_| std::env::var("HOSTNAME"))
+        .ok()
+        .or_else(|| std::fs::read_to_string("/etc/hostname").ok())
+        .map(|name| name.trim().to_string())
+        .filter(|name| !name.is_empty())
+        .unwrap_or_else(|| "unknown".to_string())
+}
+
+fn git(args: &[&str]) -> Result<String> {
+    let output = Command::new("git")
+        .args(args)
+        .output()
+        .into_diagnostic()
+        .wrap_err("failed to run git")?;
+    if !output.status.success() {
+        miette::bail!(
+            "git {} failed: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// The short hash of `HEAD`, with `-dirty` on the end if tracked files have changed since.
+pub fn current_revision() -> Result<String> {
+    let revision = git(&["rev-parse", "--short", "HEAD"])?;
+    let dirty = !git(&["status", "--porcelain", "--untracked-files=no"])?.is_empty();
+    Ok(if dirty {
+        format!("{revision}-dirty")
+    } else {
+        revision
+    })
+}
+
+/// Turns a branch or tag name into the short hash its baseline is saved under. Anything git doesn't
+/// know, like `abc123-dirty`, is taken as is.
+pub fn resolve_revision(revision: &str) -> String {
+    git(&["rev-parse", "--short", revision]).unwrap_or_else(|_| revision.to_string())
+}
+
+/// A stage's time in two baselines.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Row {
+    pub day: usize,
+    pub stage: Stage,
+    pub before: Option<Duration>,
+    pub after: Option<Duration>,
+}
+
+impl Row {
+    /// How much slower `after` is, in percent. Negative when it got faster.
+    pub fn change(&self) -> Option<f64> {
+        let before = self.before?.as_nanos() as f64;
+        let after = self.after?.as_nanos() as f64;
+        (before > 0.0).then(|| (after - before) / before * 100.0)
+    }
+
+    pub fn regressed(&self, threshold: f64) -> bool {
+        self.change().is_some_and(|change| change > threshold)
+    }
+}
+
+/// Every stage in either of two baselines, and which got slower by more than the threshold
+/// percentage.
+#[derive(Debug, Clone)]
+pub struct Comparison {
+    pub rows: Vec<Row>,
+    pub threshold: f64,
+}
+
+impl Comparison {
+    pub fn new(before: &Baseline, after: &Baseline, threshold: f64) -> Self {
+        let keys = before
+            .times
+            .keys()
+            .chain(after.times.keys())
+            .copied()
+            .collect::<BTreeSet<_>>();
+        let rows = keys
+            .into_iter()
+            .map(|(day, stage)| Row {
+                day,
+                stage,
+                before: before.get(day, stage),
+                after: after.get(day, stage),
+            })
+            .collect();
+        Self { rows, threshold }
+    }
+
+    pub fn regressions(&self) -> impl Iterator<Item = &Row> + '_ {
+        self.rows.iter().filter(|row| row.regressed(self.threshold))
+    }
+}
+
+impl Display for Comparison {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let time = |t: Option<Duration>| t.map_or_else(|| "-".to_string(), |t| format!("{t:.2?}"));
+        writeln!(
+            f,
+            "{:<5} {:<9} {:>12} {:>12} {:>9}",
+            "day", "stage", "before", "after", "change"
+        )?;
+        let mut last_day = None;
+        for row in &self.rows {
+            let day = if last_day == Some(row.day) {
+                String::new()
+            } else {
+                format!("{:02}", row.day)
+            };
+            last_day = Some(row.day);
+            let change = row
+                .change()
+                .map_or_else(|| "-".to_string(), |c| format!("{c:+.1}%"));
+            let flag = if row.regressed(self.threshold) {
+                "  slower"
+            } else {
+                ""
+            };
+            writeln!(
+                f,
+                "{day:<5} {:<9} {:>12} {:>12} {change:>9}{flag}",
+                row.stage.name(),
+                time(row.before),
+                time(row.after),
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Prints how this machine's baseline at `after` (the working tree by default) compares with
+/// `before`, and fails if any stage got more than `threshold` percent slower.
+pub fn compare(year: usize, before: &str, after: Option<&str>, threshold: f64) -> Result<()> {
+    let before = resolve_revision(before);
+    let after = match after {
+        Some(after) => resolve_revision(after),
+        None => current_revision()?,
+    };
+    let comparison = Comparison::new(
+        &Baseline::load(year, &before)?,
+        &Baseline::load(year, &after)?,
+        threshold,
+    );
+    println!("{before} -> {after} on {}\n", machine());
+    print!("{comparison}");
+    let regressions = comparison.regressions().count();
+    if regressions > 0 {
+        miette::bail!("{regressions} stage(s) got more than {threshold}% slower");
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn baseline(times: &[(usize, Stage, u64)]) -> Baseline {
+        let mut baseline = Baseline::new();
+        for &(day, stage, micros) in times {
+            baseline.insert(day, stage, Duration::from_micros(micros));
+        }
+        baseline
+    }
+
+    #[test]
+    fn round_trip() {
+        let before = baseline(&[
+            (1, Stage::GetInput, 50),
+            (1, Stage::Part1, 12),
+            (2, Stage::Part2, 83_000),
+        ]);
+        let text = before.to_string();
+        assert_eq!(text.lines().next(), Some("1\tget_input\t50000"));
+        assert_eq!(text.parse::<Baseline>().unwrap(), before);
+        assert!("1\tpart3\t5".parse::<Baseline>().is_err());
+        assert!("1\tpart1".parse::<Baseline>().is_err());
+    }
+
+    #[test]
+    fn flags_slower_stages() {
+        let before = baseline(&[(1, Stage::Part1, 100), (1, Stage::Part2, 100)]);
+        let after = baseline(&[
+            (1, Stage::Part1, 105),
+            (1, Stage::Part2, 150),
+            (2, Stage::Part1, 10),
+        ]);
+        let comparison = Comparison::new(&before, &after, 10.0);
+        assert_eq!(comparison.rows.len(), 3);
+        let regressions = comparison.regressions().collect::<Vec<_>>();
+        assert_eq!(regressions.len(), 1);
+        assert_eq!(regressions[0].stage, Stage::Part2);
+        assert_eq!(regressions[0].change(), Some(50.0));
+
+        let table = comparison.to_string();
+        assert!(table.contains("+50.0%  slower"));
+        assert!(table.contains("+5.0%\n"));
+        assert_eq!(table.lines().filter(|l| l.starts_with("01")).count(), 1);
+    }
+}
@@ -0,0 +1,218 @@
+//! The criterion harness behind every year's `benches/criterion.rs`.
+//!
+//! A year only needs `aoc_utils::bench_main!(aoc2024);`: the days come from the same registry
+//! `run_days!` builds, with a `dayNN` group per day and a benchmark per stage.
+//!
+//! Profiling is off by default. Set `AOC_PROFILER` to `flamegraph`, `cpuprofiler` or `all` to
+//! turn it on, which only takes effect with `--profile-time`. The cpuprofiler backend links
+//! against gperftools, so it also needs the year's `cpuprofiler` feature:
+//!
+//! ```sh
+//! AOC_PROFILER=flamegraph cargo bench -- day05 --profile-time 10
+//! AOC_PROFILER=cpuprofiler cargo bench --features cpuprofiler -- day05 --profile-time 10
+//! ```
+use std::{hint::black_box, path::Path, str::FromStr};
+
+#[cfg(feature = "cpuprofiler")]
+use cpuprofiler::PROFILER;
+use criterion::{profiler::Profiler, Criterion};
+use pprof::{
+    criterion::{Output, PProfProfiler},
+    flamegraph::Options,
+};
+
+pub use criterion;
+
+use crate::{answer::ToAnswer, config, load_input, utils::file::Fetch, DayVisitor, Runner};
+
+/// Which profilers run while benchmarking.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Profiling {
+    #[default]
+    Off,
+    /// pprof flamegraphs, written next to each benchmark's report.
+    Flamegraph,
+    /// gperftools' cpuprofiler, written to `benchmark.profile`.
+    CpuProfiler,
+    All,
+}
+
+impl FromStr for Profiling {
+    type Err = crate::errors::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "" | "off" | "none" => Ok(Self::Off),
+            "flamegraph" | "pprof" => Ok(Self::Flamegraph),
+            "cpuprofiler" | "gperftools" => Ok(Self::CpuProfiler),
+            "all" => Ok(Self::All),
+            _ => Err(crate::errors::Error::InvalidInput(format!(
+                "unknown profiler `{s}`, expected off, flamegraph, cpuprofiler or all"
+            ))),
+        }
+    }
+}
+
+impl Profiling {
+    pub const ENV_VAR: &'static str = "AOC_PROFILER";
+
+    /// Reads [`Profiling::ENV_VAR`]. Panics on a value it doesn't know, so a typo doesn't
+    /// silently skip the profile.
+    pub fn from_env() -> Self {
+        std::env::var(Self::ENV_VAR)
+            .map(|v| v.parse().unwrap())
+            .unwrap_or_default()
+    }
+
+    fn flamegraph(self) -> bool {
+        matches!(self, Self::Flamegraph | Self::All)
+    }
+
+    fn cpuprofiler(self) -> bool {
+        matches!(self, Self::CpuProfiler | Self::All)
+    }
+}
+
+/// Runs whichever profilers were picked, for criterion's `--profile-time` mode.
+pub struct Profilers {
+    pprof: Option<PProfProfiler<'static, 'static>>,
+    #[cfg_attr(not(feature = "cpuprofiler"), allow(dead_code))]
+    cpuprofiler: bool,
+}
+
+impl Profilers {
+    /// Panics if the cpuprofiler was asked for but wasn't compiled in.
+    pub fn new(profiling: Profiling) -> Self {
+        assert!(
+            cfg!(feature = "cpuprofiler") || !profiling.cpuprofiler(),
+            "the cpuprofiler backend needs the `cpuprofiler` feature"
+        );
+        let pprof = profiling.flamegraph().then(|| {
+            let mut options = Options::default();
+            options.flame_chart = true;
+            options.color_diffusion = true;
+            PProfProfiler::new(1000, Output::Flamegraph(Some(options)))
+        });
+        Self {
+            pprof,
+            cpuprofiler: profiling.cpuprofiler(),
+        }
+    }
+}
+
+impl Profiler for Profilers {
+    fn start_profiling(&mut self, benchmark_id: &str, benchmark_dir: &Path) {
+        if let Some(pprof) = self.pprof.as_mut() {
+            pprof.start_profiling(benchmark_id, benchmark_dir);
+        }
+        #[cfg(feature = "cpuprofiler")]
+        if self.cpuprofiler {
+            let fname = benchmark_dir.join("benchmark.profile");
+            std::fs::create_dir_all(benchmark_dir).unwrap();
+            println!("\nStarting profiling to {}", fname.display());
+            PROFILER
+                .lock()
+                .unwrap()
+                .start(fname.to_str().unwrap())
+                .unwrap();
+        }
+    }
+
+    fn stop_profiling(&mut self, benchmark_id: &str, benchmark_dir: &Path) {
+        if let Some(pprof) = self.pprof.as_mut() {
+            pprof.stop_profiling(benchmark_id, benchmark_dir);
+        }
+        #[cfg(feature = "cpuprofiler")]
+        if self.cpuprofiler {
+            PROFILER.lock().unwrap().stop().unwrap();
+        }
+    }
+}
+
+/// The criterion config for day benchmarks, with the profilers from [`Profiling::from_env`].
+pub fn config() -> Criterion {
+    match Profiling::from_env() {
+        Profiling::Off => Criterion::default(),
+        profiling => Criterion::default().with_profiler(Profilers::new(profiling)),
+    }
+}
+
+/// Benchmarks each stage of every day it visits.
+pub struct DayBencher<'c> {
+    criterion: &'c mut Criterion,
+}
+
+impl<'c> DayBencher<'c> {
+    pub fn new(criterion: &'c mut Criterion) -> Self {
+        Self { criterion }
+    }
+}
+
+impl DayVisitor for DayBencher<'_> {
+    fn visit<R, Part1, Part2>(&mut self, year: usize)
+    where
+        R: Runner<Part1, Part2>,
+        Part1: ToAnswer,
+        Part2: ToAnswer,
+    {
+        // A missing input shouldn't take down the benchmarks of every other day.
+        let input = match config::profile(None)
+            .and_then(|p| load_input(&p, year, R::day(), Fetch::Missing))
+        {
+            Ok(input) => input,
+            Err(e) => {
+                eprintln!("Skipping day {}: {e:?}", R::day());
+                return;
+            }
+        };
+        let mut group = self
+            .criterion
+            .benchmark_group(format!("day{:02}", R::day()));
+        group.bench_function("get_input", |b| b.iter(|| black_box(R::get_input(&input))));
+        match R::get_input(&input) {
+            Ok(input) => {
+                group.bench_function("part1", |b| b.iter(|| black_box(R::part1(&input))));
+                group.bench_function("part2", |b| b.iter(|| black_box(R::part2(&input))));
+            }
+            Err(e) => eprintln!("Skipping parts of day {}: {e:?}", R::day()),
+        }
+        group.finish();
+    }
+}
+
+/// Generates the `main` of a year's criterion bench from its `run_days!` registry.
+#[macro_export]
+macro_rules! bench_main {
+    ($krate:ident) => {
+        fn days(c: &mut $crate::bench::criterion::Criterion) {
+            $krate::for_each_day(&mut $crate::bench::DayBencher::new(c));
+        }
+
+        $crate::bench::criterion::criterion_group!(
+            name = benches;
+            config = $crate::bench::config();
+            targets = days
+        );
+        $crate::bench::criterion::criterion_main!(benches);
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn profiling_names() {
+        assert_eq!("".parse::<Profiling>().unwrap(), Profiling::Off);
+        assert_eq!(
+            "Flamegraph".parse::<Profiling>().unwrap(),
+            Profiling::Flamegraph
+        );
+        assert_eq!(
+            "cpuprofiler".parse::<Profiling>().unwrap(),
+            Profiling::CpuProfiler
+        );
+        assert!("all".parse::<Profiling>().unwrap().flamegraph());
+        assert!("perf".parse::<Profiling>().is_err());
+    }
+}
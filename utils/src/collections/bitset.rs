@@ -1,17 +1,52 @@
+use std::hash::{Hash, Hasher};
+use std::ops::{BitAnd, BitAndAssign, BitOr, BitOrAssign, BitXor, BitXorAssign, Sub, SubAssign};
+
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum Error {
+    #[error("mismatched bitset dimensions: {0:?} vs {1:?}")]
+    DimMismatch(Dim, Dim),
+    #[error("value {0:?} is out of bounds for {1:?}")]
+    OutOfBounds(Box<[isize]>, Dim),
+}
+
 pub trait ToBitSetIndex {
     fn to_bitset_index(&self, dim: &Dim) -> usize;
+
+    /// Checked version of [`ToBitSetIndex::to_bitset_index`].
+    ///
+    /// The default implementation can't know how the index was built, so it never fails.
+    fn try_to_bitset_index(&self, dim: &Dim) -> Result<usize, Error> {
+        Ok(self.to_bitset_index(dim))
+    }
+}
+
+fn check_capacity(index: usize, dim: &Dim) -> Result<usize, Error> {
+    match dim.capacity() {
+        Some(capacity) if index >= capacity => {
+            Err(Error::OutOfBounds(Box::new([index as isize]), dim.clone()))
+        }
+        _ => Ok(index),
+    }
 }
 
 impl ToBitSetIndex for usize {
     fn to_bitset_index(&self, _dim: &Dim) -> usize {
         *self
     }
+
+    fn try_to_bitset_index(&self, dim: &Dim) -> Result<usize, Error> {
+        check_capacity(*self, dim)
+    }
 }
 
 impl ToBitSetIndex for char {
     fn to_bitset_index(&self, _dim: &Dim) -> usize {
         *self as usize
     }
+
+    fn try_to_bitset_index(&self, dim: &Dim) -> Result<usize, Error> {
+        check_capacity(*self as usize, dim)
+    }
 }
 
 pub trait FromBitSetIndex {
@@ -35,25 +70,56 @@ pub enum Dim {
     #[default]
     Unbounded,
     Fixed(Box<[usize]>),
+    /// Bounds whose minimum corner sits at the given origin instead of at zero.
+    Offset(Box<[isize]>, Box<[usize]>),
 }
 
 pub trait Dimension {
     fn bounds(&self) -> Option<&[usize]>;
     fn capacity(&self) -> Option<usize>;
+
+    fn origin(&self) -> Option<&[isize]> {
+        None
+    }
+
+    /// Translates `point` so that the origin is at zero, or returns `None` if it is outside the
+    /// bounds. Axes past the end of the bounds are only required to be non-negative.
+    fn normalize<const N: usize>(&self, point: [isize; N]) -> Option<[usize; N]> {
+        let bounds = self.bounds()?;
+        let mut out = [0; N];
+        for (axis, (out, p)) in out.iter_mut().zip(point).enumerate() {
+            let p = p - self
+                .origin()
+                .map_or(0, |o| o.get(axis).copied().unwrap_or(0));
+            let p = usize::try_from(p).ok()?;
+            if bounds.get(axis).is_some_and(|&b| p >= b) {
+                return None;
+            }
+            *out = p;
+        }
+        Some(out)
+    }
 }
 
 impl Dimension for Dim {
     fn bounds(&self) -> Option<&[usize]> {
         match self {
             Dim::Unbounded => None,
-            Dim::Fixed(v) => Some(v),
+            Dim::Fixed(v) | Dim::Offset(_, v) => Some(v),
         }
     }
 
     fn capacity(&self) -> Option<usize> {
         match self {
             Dim::Unbounded => None,
-            Dim::Fixed(v) => Some(v.iter().product()),
+            Dim::Fixed(v) | Dim::Offset(_, v) => Some(v.iter().product()),
+        }
+    }
+
+    fn origin(&self) -> Option<&[isize]> {
+        match self {
+            Dim::Offset(o, _) => Some(o),
+            _ => None,
         }
     }
 }
@@ -105,19 +171,42 @@ impl<'a> From<&'a [usize]> for Dim {
     }
 }
 
+impl<const N: usize> From<([isize; N], [usize; N])> for Dim {
+    fn from((origin, bounds): ([isize; N], [usize; N])) -> Self {
+        Dim::Offset(Box::new(origin), Box::new(bounds))
+    }
+}
+
 impl From<Unbounded> for Dim {
     fn from(_: Unbounded) -> Self {
         Dim::Unbounded
     }
 }
 
-#[derive(PartialEq, Eq, Clone)]
+#[derive(PartialEq, Eq)]
 pub struct BitSet<T> {
     set: bit_set::BitSet,
     dim: Dim,
     _marker: std::marker::PhantomData<T>,
 }
 
+impl<T> Clone for BitSet<T> {
+    fn clone(&self) -> Self {
+        Self {
+            set: self.set.clone(),
+            dim: self.dim.clone(),
+            _marker: std::marker::PhantomData,
+        }
+    }
+}
+
+impl<T> Hash for BitSet<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.set.hash(state);
+        self.dim.hash(state);
+    }
+}
+
 impl<T> Default for BitSet<T>
 where
     T: ToBitSetIndex,
@@ -167,12 +256,37 @@ where
         }
     }
 
-    pub fn insert(&mut self, value: T) {
-        self.set.insert(value.to_bitset_index(&self.dim));
+    pub fn dim(&self) -> &Dim {
+        &self.dim
+    }
+
+    /// Panics if `value` is outside of the bounds of this set, rather than aliasing another value.
+    pub fn insert(&mut self, value: T) -> bool {
+        let index = value
+            .try_to_bitset_index(&self.dim)
+            .unwrap_or_else(|e| panic!("{e}"));
+        self.set.insert(index)
+    }
+
+    /// Like [`BitSet::insert`], but returns an error instead of aliasing another value when
+    /// `value` is outside of the bounds of this set.
+    pub fn try_insert(&mut self, value: T) -> Result<bool, Error> {
+        let index = value.try_to_bitset_index(&self.dim)?;
+        Ok(self.set.insert(index))
+    }
+
+    /// Returns `false` for values outside of the bounds of this set, which can't be in it.
+    pub fn remove(&mut self, value: &T) -> bool {
+        value
+            .try_to_bitset_index(&self.dim)
+            .is_ok_and(|index| self.set.remove(index))
     }
 
+    /// Returns `false` for values outside of the bounds of this set.
     pub fn contains(&self, value: &T) -> bool {
-        self.set.contains(value.to_bitset_index(&self.dim))
+        value
+            .try_to_bitset_index(&self.dim)
+            .is_ok_and(|index| self.set.contains(index))
     }
 
     pub fn clear(&mut self) {
@@ -180,6 +294,67 @@ where
     }
 }
 
+impl<T> BitSet<T> {
+    pub fn len(&self) -> usize {
+        self.set.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.set.is_empty()
+    }
+
+    /// Returns an error if `other` indexes its values with a different [`Dim`],
+    /// since the raw bits of the two sets would then mean different things.
+    pub fn check_dim(&self, other: &Self) -> Result<(), Error> {
+        if self.dim == other.dim {
+            Ok(())
+        } else {
+            Err(Error::DimMismatch(self.dim.clone(), other.dim.clone()))
+        }
+    }
+
+    fn assert_dim(&self, other: &Self) {
+        if let Err(e) = self.check_dim(other) {
+            panic!("{e}");
+        }
+    }
+
+    pub fn is_subset(&self, other: &Self) -> bool {
+        self.assert_dim(other);
+        self.set.is_subset(&other.set)
+    }
+
+    pub fn is_superset(&self, other: &Self) -> bool {
+        self.assert_dim(other);
+        self.set.is_superset(&other.set)
+    }
+
+    pub fn is_disjoint(&self, other: &Self) -> bool {
+        self.assert_dim(other);
+        self.set.is_disjoint(&other.set)
+    }
+
+    pub fn union_with(&mut self, other: &Self) {
+        self.assert_dim(other);
+        self.set.union_with(&other.set);
+    }
+
+    pub fn intersect_with(&mut self, other: &Self) {
+        self.assert_dim(other);
+        self.set.intersect_with(&other.set);
+    }
+
+    pub fn difference_with(&mut self, other: &Self) {
+        self.assert_dim(other);
+        self.set.difference_with(&other.set);
+    }
+
+    pub fn symmetric_difference_with(&mut self, other: &Self) {
+        self.assert_dim(other);
+        self.set.symmetric_difference_with(&other.set);
+    }
+}
+
 impl<T> BitSet<T>
 where
     T: ToBitSetIndex + FromBitSetIndex,
@@ -194,7 +369,9 @@ where
     T: ToBitSetIndex,
 {
     fn extend<Iter: IntoIterator<Item = T>>(&mut self, iter: Iter) {
-        iter.into_iter().for_each(|i| self.insert(i));
+        iter.into_iter().for_each(|i| {
+            self.insert(i);
+        });
     }
 }
 
@@ -219,11 +396,113 @@ where
 {
     fn from_iter<T: IntoIterator<Item = A>>(iter: T) -> Self {
         let mut this = Self::default();
-        iter.into_iter().for_each(|i| this.insert(i));
+        iter.into_iter().for_each(|i| {
+            this.insert(i);
+        });
+        this
+    }
+}
+
+impl<T> BitOr<&BitSet<T>> for &BitSet<T> {
+    type Output = BitSet<T>;
+
+    fn bitor(self, rhs: &BitSet<T>) -> Self::Output {
+        let mut this = self.clone();
+        this.union_with(rhs);
+        this
+    }
+}
+
+impl<T> BitOr for BitSet<T> {
+    type Output = BitSet<T>;
+
+    fn bitor(mut self, rhs: BitSet<T>) -> Self::Output {
+        self.union_with(&rhs);
+        self
+    }
+}
+
+impl<T> BitOrAssign<&BitSet<T>> for BitSet<T> {
+    fn bitor_assign(&mut self, rhs: &BitSet<T>) {
+        self.union_with(rhs);
+    }
+}
+
+impl<T> BitAnd<&BitSet<T>> for &BitSet<T> {
+    type Output = BitSet<T>;
+
+    fn bitand(self, rhs: &BitSet<T>) -> Self::Output {
+        let mut this = self.clone();
+        this.intersect_with(rhs);
+        this
+    }
+}
+
+impl<T> BitAnd for BitSet<T> {
+    type Output = BitSet<T>;
+
+    fn bitand(mut self, rhs: BitSet<T>) -> Self::Output {
+        self.intersect_with(&rhs);
+        self
+    }
+}
+
+impl<T> BitAndAssign<&BitSet<T>> for BitSet<T> {
+    fn bitand_assign(&mut self, rhs: &BitSet<T>) {
+        self.intersect_with(rhs);
+    }
+}
+
+impl<T> Sub<&BitSet<T>> for &BitSet<T> {
+    type Output = BitSet<T>;
+
+    fn sub(self, rhs: &BitSet<T>) -> Self::Output {
+        let mut this = self.clone();
+        this.difference_with(rhs);
         this
     }
 }
 
+impl<T> Sub for BitSet<T> {
+    type Output = BitSet<T>;
+
+    fn sub(mut self, rhs: BitSet<T>) -> Self::Output {
+        self.difference_with(&rhs);
+        self
+    }
+}
+
+impl<T> SubAssign<&BitSet<T>> for BitSet<T> {
+    fn sub_assign(&mut self, rhs: &BitSet<T>) {
+        self.difference_with(rhs);
+    }
+}
+
+impl<T> BitXor<&BitSet<T>> for &BitSet<T> {
+    type Output = BitSet<T>;
+
+    fn bitxor(self, rhs: &BitSet<T>) -> Self::Output {
+        let mut this = self.clone();
+        this.symmetric_difference_with(rhs);
+        this
+    }
+}
+
+impl<T> BitXor for BitSet<T> {
+    type Output = BitSet<T>;
+
+    fn bitxor(mut self, rhs: BitSet<T>) -> Self::Output {
+        self.symmetric_difference_with(&rhs);
+        self
+    }
+}
+
+impl<T> BitXorAssign<&BitSet<T>> for BitSet<T> {
+    fn bitxor_assign(&mut self, rhs: &BitSet<T>) {
+        self.symmetric_difference_with(rhs);
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -237,4 +516,50 @@ mod tests {
         assert!(set.contains(&3));
         assert!(!set.contains(&5));
     }
+
+    #[test]
+    fn set_ops() {
+        let a: BitSet<usize> = BitSet::from_iter([1, 2, 3]);
+        let b: BitSet<usize> = BitSet::from_iter([3, 4]);
+        assert_eq!((&a | &b).iter().collect::<Vec<_>>(), vec![1, 2, 3, 4]);
+        assert_eq!((&a & &b).iter().collect::<Vec<_>>(), vec![3]);
+        assert_eq!((&a - &b).iter().collect::<Vec<_>>(), vec![1, 2]);
+        assert_eq!((&a ^ &b).iter().collect::<Vec<_>>(), vec![1, 2, 4]);
+        assert!((&a & &b).is_subset(&a));
+        assert!(!a.is_subset(&b));
+
+        let mut c = a.clone();
+        c |= &b;
+        assert_eq!(c.len(), 4);
+        assert!(c.remove(&4));
+        assert!(!c.remove(&4));
+        assert_eq!(c, a);
+    }
+
+    #[test]
+    fn try_insert_out_of_bounds() {
+        let mut set: BitSet<usize> = BitSet::with_bounds(&[10]);
+        assert_eq!(set.try_insert(9), Ok(true));
+        assert_eq!(
+            set.try_insert(10),
+            Err(Error::OutOfBounds(Box::new([10]), Dim::from(10)))
+        );
+        assert!(!set.contains(&10));
+        assert!(!set.remove(&10));
+    }
+
+    #[test]
+    #[should_panic(expected = "out of bounds")]
+    fn insert_out_of_bounds() {
+        let mut set: BitSet<usize> = BitSet::with_bounds(&[10]);
+        set.insert(10);
+    }
+
+    #[test]
+    #[should_panic(expected = "mismatched bitset dimensions")]
+    fn set_ops_dim_mismatch() {
+        let a: BitSet<usize> = BitSet::with_bounds(&[10]);
+        let b: BitSet<usize> = BitSet::with_bounds(&[20]);
+        let _ = &a | &b;
+    }
 }
@@ -0,0 +1,474 @@
+use std::ops::{BitAnd, BitOr, RangeInclusive, Sub};
+
+use num::PrimInt;
+
+use crate::traits::RangeIncExt;
+
+/// The integer types an [`IntervalSet`] can hold.
+pub trait IntervalBound: PrimInt + TryInto<usize, Error: std::fmt::Debug> {}
+
+impl<T> IntervalBound for T where T: PrimInt + TryInto<usize, Error: std::fmt::Debug> {}
+
+/// A set of integers stored as sorted, disjoint and non-adjacent inclusive ranges.
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct IntervalSet<T> {
+    ranges: Vec<RangeInclusive<T>>,
+}
+
+impl<T> Default for IntervalSet<T> {
+    fn default() -> Self {
+        Self { ranges: Vec::new() }
+    }
+}
+
+impl<T> std::fmt::Debug for IntervalSet<T>
+where
+    T: std::fmt::Debug,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_set().entries(self.ranges.iter()).finish()
+    }
+}
+
+/// Whether `a` and `b` overlap or sit right next to each other, so they can be merged.
+fn touches<T: IntervalBound>(a: &RangeInclusive<T>, b: &RangeInclusive<T>) -> bool {
+    *a.start() <= b.end().saturating_add(T::one()) && *b.start() <= a.end().saturating_add(T::one())
+}
+
+/// `end - start` of a non-empty range, worked out in 128 bits so that it can't overflow `T`.
+fn width<T: IntervalBound>(range: &RangeInclusive<T>) -> u128 {
+    if T::min_value() < T::zero() {
+        let (start, end) = (range.start().to_i128(), range.end().to_i128());
+        end.unwrap().wrapping_sub(start.unwrap()) as u128
+    } else {
+        range.end().to_u128().unwrap() - range.start().to_u128().unwrap()
+    }
+}
+
+impl<T> IntervalSet<T>
+where
+    T: IntervalBound,
+{
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn insert(&mut self, range: RangeInclusive<T>) {
+        if range.is_empty() {
+            return;
+        }
+        let first = self
+            .ranges
+            .partition_point(|r| !touches(r, &range) && r.end() < range.start());
+        let last = first + self.ranges[first..].partition_point(|r| touches(r, &range));
+        let merged = self.ranges[first..last].iter().fold(range, |acc, r| {
+            (*acc.start()).min(*r.start())..=(*acc.end()).max(*r.end())
+        });
+        self.ranges.splice(first..last, [merged]);
+    }
+
+    pub fn remove(&mut self, range: RangeInclusive<T>) {
+        if range.is_empty() {
+            return;
+        }
+        let first = self.ranges.partition_point(|r| r.end() < range.start());
+        let last = first + self.ranges[first..].partition_point(|r| r.start() <= range.end());
+        let mut kept = Vec::with_capacity(2);
+        if let Some(r) = self.ranges.get(first).filter(|_| first < last) {
+            if r.start() < range.start() {
+                kept.push(*r.start()..=*range.start() - T::one());
+            }
+        }
+        if let Some(r) = self
+            .ranges
+            .get(last.wrapping_sub(1))
+            .filter(|_| first < last)
+        {
+            if r.end() > range.end() {
+                kept.push(*range.end() + T::one()..=*r.end());
+            }
+        }
+        self.ranges.splice(first..last, kept);
+    }
+
+    fn index_of(&self, value: &T) -> Option<usize> {
+        let i = self.ranges.partition_point(|r| r.end() < value);
+        self.ranges.get(i).filter(|r| r.contains(value)).map(|_| i)
+    }
+
+    pub fn contains(&self, value: &T) -> bool {
+        self.index_of(value).is_some()
+    }
+
+    /// Whether every value of `range` is in the set.
+    pub fn contains_range(&self, range: &RangeInclusive<T>) -> bool {
+        range.is_empty()
+            || self
+                .index_of(range.start())
+                .is_some_and(|i| range.inside(&self.ranges[i]))
+    }
+
+    /// Whether any value of `range` is in the set. Never for an empty range.
+    pub fn overlaps(&self, range: &RangeInclusive<T>) -> bool {
+        if range.is_empty() {
+            return false;
+        }
+        let i = self.ranges.partition_point(|r| r.end() < range.start());
+        self.ranges.get(i).is_some_and(|r| r.overlaps(range))
+    }
+
+    /// The range containing `value`, if any.
+    pub fn range_of(&self, value: &T) -> Option<&RangeInclusive<T>> {
+        self.index_of(value).map(|i| &self.ranges[i])
+    }
+
+    pub fn ranges(&self) -> impl ExactSizeIterator<Item = &RangeInclusive<T>> + '_ {
+        self.ranges.iter()
+    }
+
+    /// Number of disjoint ranges in the set.
+    pub fn ranges_len(&self) -> usize {
+        self.ranges.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+
+    /// Number of values in the set. Only a 128-bit set holding more than `u128::MAX` values, i.e.
+    /// all of them, is too big to count, and comes out as `u128::MAX`.
+    pub fn size(&self) -> u128 {
+        self.ranges
+            .iter()
+            .map(|r| width(r).saturating_add(1))
+            .fold(0, u128::saturating_add)
+    }
+
+    pub fn min(&self) -> Option<T> {
+        self.ranges.first().map(|r| *r.start())
+    }
+
+    pub fn max(&self) -> Option<T> {
+        self.ranges.last().map(|r| *r.end())
+    }
+
+    pub fn clear(&mut self) {
+        self.ranges.clear();
+    }
+
+    /// Every value of `bounds` that is not in the set.
+    pub fn complement(&self, bounds: RangeInclusive<T>) -> Self {
+        let mut this = Self::from_iter([bounds]);
+        self.ranges.iter().for_each(|r| this.remove(r.clone()));
+        this
+    }
+
+    pub fn union(&self, other: &Self) -> Self {
+        let mut this = self.clone();
+        other.ranges.iter().for_each(|r| this.insert(r.clone()));
+        this
+    }
+
+    pub fn intersection(&self, other: &Self) -> Self {
+        let mut ranges = Vec::new();
+        let (mut a, mut b) = (
+            self.ranges.iter().peekable(),
+            other.ranges.iter().peekable(),
+        );
+        while let (Some(ra), Some(rb)) = (a.peek(), b.peek()) {
+            if ra.overlaps(rb) {
+                ranges.push(ra.intersect(rb));
+            }
+            if ra.end() < rb.end() {
+                a.next();
+            } else {
+                b.next();
+            }
+        }
+        Self { ranges }
+    }
+
+    pub fn difference(&self, other: &Self) -> Self {
+        let mut this = self.clone();
+        other.ranges.iter().for_each(|r| this.remove(r.clone()));
+        this
+    }
+}
+
+impl<T> FromIterator<RangeInclusive<T>> for IntervalSet<T>
+where
+    T: IntervalBound,
+{
+    fn from_iter<I: IntoIterator<Item = RangeInclusive<T>>>(iter: I) -> Self {
+        let mut this = Self::new();
+        this.extend(iter);
+        this
+    }
+}
+
+impl<T> Extend<RangeInclusive<T>> for IntervalSet<T>
+where
+    T: IntervalBound,
+{
+    fn extend<I: IntoIterator<Item = RangeInclusive<T>>>(&mut self, iter: I) {
+        iter.into_iter().for_each(|r| self.insert(r));
+    }
+}
+
+impl<T> IntoIterator for IntervalSet<T> {
+    type Item = RangeInclusive<T>;
+    type IntoIter = std::vec::IntoIter<RangeInclusive<T>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.ranges.into_iter()
+    }
+}
+
+impl<T: IntervalBound> BitOr<&IntervalSet<T>> for &IntervalSet<T> {
+    type Output = IntervalSet<T>;
+
+    fn bitor(self, rhs: &IntervalSet<T>) -> Self::Output {
+        self.union(rhs)
+    }
+}
+
+impl<T: IntervalBound> BitAnd<&IntervalSet<T>> for &IntervalSet<T> {
+    type Output = IntervalSet<T>;
+
+    fn bitand(self, rhs: &IntervalSet<T>) -> Self::Output {
+        self.intersection(rhs)
+    }
+}
+
+impl<T: IntervalBound> Sub<&IntervalSet<T>> for &IntervalSet<T> {
+    type Output = IntervalSet<T>;
+
+    fn sub(self, rhs: &IntervalSet<T>) -> Self::Output {
+        self.difference(rhs)
+    }
+}
+
+/// A piecewise translation: each source range is moved so that it starts at its destination,
+/// and values outside of every source range map to themselves.
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct RangeShiftMap<T> {
+    /// Sorted by source start, sources never overlap.
+    pieces: Vec<(RangeInclusive<T>, T)>,
+}
+
+impl<T> Default for RangeShiftMap<T> {
+    fn default() -> Self {
+        Self { pieces: Vec::new() }
+    }
+}
+
+impl<T> std::fmt::Debug for RangeShiftMap<T>
+where
+    T: std::fmt::Debug,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_map()
+            .entries(self.pieces.iter().map(|(s, d)| (s, d)))
+            .finish()
+    }
+}
+
+impl<T> RangeShiftMap<T>
+where
+    T: IntervalBound,
+{
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Maps `source` onto the range of the same length starting at `dest`.
+    ///
+    /// Panics if `source` overlaps a range that is already mapped.
+    pub fn insert(&mut self, source: RangeInclusive<T>, dest: T) {
+        if source.is_empty() {
+            return;
+        }
+        let i = self
+            .pieces
+            .partition_point(|(s, _)| s.end() < source.start());
+        assert!(
+            self.pieces.get(i).is_none_or(|(s, _)| !s.overlaps(&source)),
+            "overlapping source ranges in RangeShiftMap"
+        );
+        self.pieces.insert(i, (source, dest));
+    }
+
+    /// The `destination source length` triples used by almanac-style puzzle inputs.
+    pub fn insert_len(&mut self, dest: T, source: T, len: T) {
+        if len > T::zero() {
+            self.insert(source..=source + (len - T::one()), dest);
+        }
+    }
+
+    fn shift(piece: &(RangeInclusive<T>, T), value: T) -> T {
+        piece.1 + (value - *piece.0.start())
+    }
+
+    pub fn map(&self, value: T) -> T {
+        let i = self.pieces.partition_point(|(s, _)| *s.end() < value);
+        match self.pieces.get(i) {
+            Some(piece) if piece.0.contains(&value) => Self::shift(piece, value),
+            _ => value,
+        }
+    }
+
+    /// The image of every value in `range`.
+    pub fn map_range(&self, range: RangeInclusive<T>) -> IntervalSet<T> {
+        let mut out = IntervalSet::new();
+        if range.is_empty() {
+            return out;
+        }
+        let mut next = *range.start();
+        let first = self
+            .pieces
+            .partition_point(|(s, _)| s.end() < range.start());
+        for piece in self.pieces[first..]
+            .iter()
+            .take_while(|(s, _)| s.start() <= range.end())
+        {
+            let overlap = piece.0.intersect(&range);
+            if next < *overlap.start() {
+                out.insert(next..=*overlap.start() - T::one());
+            }
+            out.insert(Self::shift(piece, *overlap.start())..=Self::shift(piece, *overlap.end()));
+            match overlap.end().checked_add(&T::one()) {
+                Some(n) => next = n,
+                None => return out,
+            }
+        }
+        if next <= *range.end() {
+            out.insert(next..=*range.end());
+        }
+        out
+    }
+
+    /// The image of every value in `set`.
+    pub fn map_set(&self, set: &IntervalSet<T>) -> IntervalSet<T> {
+        set.ranges()
+            .flat_map(|r| self.map_range(r.clone()))
+            .collect()
+    }
+}
+
+impl<T> FromIterator<(RangeInclusive<T>, T)> for RangeShiftMap<T>
+where
+    T: IntervalBound,
+{
+    fn from_iter<I: IntoIterator<Item = (RangeInclusive<T>, T)>>(iter: I) -> Self {
+        let mut this = Self::new();
+        iter.into_iter().for_each(|(s, d)| this.insert(s, d));
+        this
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ranges<T: IntervalBound>(set: &IntervalSet<T>) -> Vec<RangeInclusive<T>> {
+        set.ranges().cloned().collect()
+    }
+
+    #[test]
+    fn insert_merges() {
+        let mut set = IntervalSet::new();
+        set.insert(10..=12);
+        set.insert(0..=2);
+        set.insert(5..=6);
+        assert_eq!(ranges(&set), vec![0..=2, 5..=6, 10..=12]);
+        set.insert(3..=4);
+        assert_eq!(ranges(&set), vec![0..=6, 10..=12]);
+        set.insert(8..=20);
+        assert_eq!(ranges(&set), vec![0..=6, 8..=20]);
+        set.insert(-5..=30);
+        assert_eq!(ranges(&set), vec![-5..=30]);
+        assert_eq!(set.size(), 36);
+    }
+
+    #[test]
+    fn remove_splits() {
+        let mut set = IntervalSet::from_iter([0..=10, 20..=30]);
+        set.remove(5..=25);
+        assert_eq!(ranges(&set), vec![0..=4, 26..=30]);
+        set.remove(0..=0);
+        set.remove(30..=40);
+        assert_eq!(ranges(&set), vec![1..=4, 26..=29]);
+        set.remove(-10..=100);
+        assert!(set.is_empty());
+    }
+
+    #[test]
+    fn queries() {
+        let set = IntervalSet::from_iter([0..=4, 10..=14]);
+        assert!(set.contains(&0));
+        assert!(set.contains(&14));
+        assert!(!set.contains(&5));
+        assert!(set.contains_range(&(1..=3)));
+        assert!(!set.contains_range(&(3..=10)));
+        assert!(set.overlaps(&(3..=10)));
+        assert!(!set.overlaps(&(5..=9)));
+        assert!(!set.overlaps(&RangeInclusive::new(3, 2)));
+        assert_eq!(set.ranges_len(), 2);
+        assert_eq!(set.size(), 10);
+        assert_eq!(set.range_of(&12), Some(&(10..=14)));
+        assert_eq!((set.min(), set.max()), (Some(0), Some(14)));
+    }
+
+    #[test]
+    fn huge_sizes() {
+        let all = IntervalSet::from_iter([i64::MIN..=i64::MAX]);
+        assert_eq!(all.size(), 1 << 64);
+        let all = IntervalSet::from_iter([0..=u64::MAX]);
+        assert_eq!(all.size(), 1 << 64);
+        let halves = IntervalSet::from_iter([0..=u64::MAX / 2 - 1, u64::MAX / 2 + 1..=u64::MAX]);
+        assert_eq!(halves.size(), u128::from(u64::MAX));
+        let all = IntervalSet::from_iter([i128::MIN..=i128::MAX]);
+        assert_eq!(all.size(), u128::MAX);
+        assert_eq!(IntervalSet::<i8>::from_iter([-128..=127]).size(), 256);
+    }
+
+    #[test]
+    fn set_ops() {
+        let a = IntervalSet::from_iter([0..=10, 20..=30]);
+        let b = IntervalSet::from_iter([5..=25]);
+        assert_eq!(ranges(&(&a | &b)), vec![0..=30]);
+        assert_eq!(ranges(&(&a & &b)), vec![5..=10, 20..=25]);
+        assert_eq!(ranges(&(&a - &b)), vec![0..=4, 26..=30]);
+        assert_eq!(
+            ranges(&a.complement(-5..=35)),
+            vec![-5..=-1, 11..=19, 31..=35]
+        );
+        assert_eq!(
+            ranges(&IntervalSet::<u8>::new().complement(0..=255)),
+            vec![0..=255]
+        );
+    }
+
+    #[test]
+    fn shift_map() {
+        // seed-to-soil map from the 2023 day 5 example
+        let mut map = RangeShiftMap::new();
+        map.insert_len(50u64, 98, 2);
+        map.insert_len(52, 50, 48);
+        assert_eq!(map.map(79), 81);
+        assert_eq!(map.map(14), 14);
+        assert_eq!(map.map(98), 50);
+
+        let seeds = IntervalSet::from_iter([79..=92, 55..=67]);
+        assert_eq!(ranges(&map.map_set(&seeds)), vec![57..=69, 81..=94]);
+        assert_eq!(ranges(&map.map_range(0..=99)), vec![0..=99]);
+        assert_eq!(ranges(&map.map_range(45..=99)), vec![45..=99]);
+        assert_eq!(ranges(&map.map_range(97..=100)), vec![50..=51, 99..=100]);
+    }
+
+    #[test]
+    #[should_panic(expected = "overlapping source ranges")]
+    fn shift_map_overlap() {
+        RangeShiftMap::from_iter([(0..=10, 100), (5..=15, 200)]);
+    }
+}
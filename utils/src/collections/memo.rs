@@ -0,0 +1,185 @@
+use std::{borrow::Borrow, hash::Hash};
+
+use hashbrown::{DefaultHashBuilder, HashMap};
+
+/// A memoization table that is passed around explicitly instead of living in a static, so every
+/// call starts cold and keys can borrow from the puzzle input.
+///
+/// The closures given to [`Memo::memoize`] receive the table back, which is how recursive
+/// functions reach it:
+///
+/// ```
+/// use aoc_utils::collections::Memo;
+///
+/// fn fib(n: u64, memo: &mut Memo<u64, u64>) -> u64 {
+///     if n < 2 {
+///         return n;
+///     }
+///     memo.memoize(n, |memo| fib(n - 1, memo) + fib(n - 2, memo))
+/// }
+///
+/// assert_eq!(fib(90, &mut Memo::new()), 2880067194370816120);
+/// ```
+#[derive(Clone)]
+pub struct Memo<K, V, S = DefaultHashBuilder> {
+    table: HashMap<K, V, S>,
+    hits: usize,
+}
+
+impl<K, V> Memo<K, V> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            table: HashMap::with_capacity(capacity),
+            hits: 0,
+        }
+    }
+}
+
+impl<K, V, S> Default for Memo<K, V, S>
+where
+    S: Default,
+{
+    fn default() -> Self {
+        Self {
+            table: HashMap::default(),
+            hits: 0,
+        }
+    }
+}
+
+impl<K, V, S> std::fmt::Debug for Memo<K, V, S>
+where
+    K: std::fmt::Debug,
+    V: std::fmt::Debug,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Memo")
+            .field("table", &self.table)
+            .field("hits", &self.hits)
+            .finish()
+    }
+}
+
+impl<K, V, S> Memo<K, V, S>
+where
+    K: Hash + Eq,
+    S: std::hash::BuildHasher,
+{
+    pub fn get<Q>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.table.get(key)
+    }
+
+    pub fn insert(&mut self, key: K, value: V) -> Option<V> {
+        self.table.insert(key, value)
+    }
+
+    /// The value cached for `key`, computing and storing it with `f` first if there isn't one.
+    pub fn memoize<F>(&mut self, key: K, f: F) -> V
+    where
+        V: Clone,
+        F: FnOnce(&mut Self) -> V,
+    {
+        if let Some(value) = self.table.get(&key) {
+            self.hits += 1;
+            return value.clone();
+        }
+        let value = f(self);
+        self.table.insert(key, value.clone());
+        value
+    }
+
+    /// Like [`Memo::memoize`], but only builds an owned key on a miss, so a `Memo<String, _>` can
+    /// be queried with a `&str`.
+    pub fn memoize_ref<Q, F>(&mut self, key: &Q, f: F) -> V
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ToOwned<Owned = K> + ?Sized,
+        V: Clone,
+        F: FnOnce(&mut Self) -> V,
+    {
+        if let Some(value) = self.table.get(key) {
+            self.hits += 1;
+            return value.clone();
+        }
+        let value = f(self);
+        self.table.insert(key.to_owned(), value.clone());
+        value
+    }
+}
+
+impl<K, V, S> Memo<K, V, S> {
+    /// Forgets every cached value, so the next run does all of the work again.
+    pub fn clear(&mut self) {
+        self.table.clear();
+        self.hits = 0;
+    }
+
+    pub fn len(&self) -> usize {
+        self.table.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.table.is_empty()
+    }
+
+    /// How many lookups were answered from the table since it was created or cleared.
+    pub fn hits(&self) -> usize {
+        self.hits
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ways(goal: &str, parts: &[&str], memo: &mut Memo<String, usize>) -> usize {
+        if goal.is_empty() {
+            return 1;
+        }
+        memo.memoize_ref(goal, |memo| {
+            parts
+                .iter()
+                .filter_map(|p| goal.strip_prefix(p))
+                .map(|rest| ways(rest, parts, memo))
+                .sum()
+        })
+    }
+
+    #[test]
+    fn borrowed_keys() {
+        let parts = ["r", "wr", "b", "g", "bwu", "rb", "gb", "br"];
+        let mut memo = Memo::new();
+        assert_eq!(ways("rrbgbr", &parts, &mut memo), 6);
+        assert_eq!(memo.get("gbr"), Some(&3));
+        assert!(memo.hits() > 0);
+
+        memo.clear();
+        assert!(memo.is_empty());
+        assert_eq!(memo.hits(), 0);
+        assert_eq!(ways("bwurrg", &parts, &mut memo), 1);
+    }
+
+    #[test]
+    fn keys_borrowing_input() {
+        let input = String::from("abcabc");
+        let mut memo: Memo<&str, usize> = Memo::new();
+        let mut calls = 0;
+        for start in [0, 3, 0] {
+            let key = &input[start..start + 3];
+            memo.memoize(key, |_| {
+                calls += 1;
+                key.len()
+            });
+        }
+        assert_eq!(calls, 1);
+        assert_eq!(memo.len(), 1);
+    }
+}
@@ -1,8 +1,14 @@
 pub mod bitset;
+pub mod interval;
+pub mod memo;
 pub mod multimap;
 pub mod multiset;
+pub mod small_bitset;
 
-#[derive(Debug, Default, PartialEq, Eq)]
+pub use memo::Memo;
+pub use small_bitset::SmallBitSet;
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
 #[repr(transparent)]
 pub struct HVec<T, const N: usize = 10>(heapless::Vec<T, N>);
 
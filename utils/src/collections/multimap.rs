@@ -189,6 +189,72 @@ where
         });
         self.inner.retain(|_, set| !set.is_empty());
     }
+
+    /// Removes a single `v` from the values of `k`, dropping `k` if it has no values left.
+    pub fn remove_value<Q>(&mut self, k: &Q, v: &V) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        let Some(set) = self.inner.get_mut(k) else {
+            return false;
+        };
+        let removed = set.remove(v);
+        if set.is_empty() {
+            self.inner.remove(k);
+        }
+        removed
+    }
+
+    /// How many values `k` has.
+    pub fn len_values<Q>(&self, k: &Q) -> usize
+    where
+        K: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        self.inner.get(k).map_or(0, HashSet::len)
+    }
+
+    pub fn values(&self) -> hash_map::Values<'_, K, HashSet<V, S, A>> {
+        self.inner.values()
+    }
+
+    pub fn flat_values(&self) -> impl Iterator<Item = &'_ V> {
+        self.inner.values().flatten()
+    }
+
+    /// Builds the reverse map, from each value to every key that maps to it.
+    pub fn invert(&self) -> MultiMap<V, K, S, A>
+    where
+        K: Clone,
+        V: Clone,
+        A: Clone,
+    {
+        let mut inverted = MultiMap::with_capacity_and_hasher_in(
+            self.inner.len(),
+            self.inner.hasher().clone(),
+            self.inner.allocator().clone(),
+        );
+        self.flat_iter()
+            .for_each(|(k, v)| inverted.insert(v.clone(), k.clone()));
+        inverted
+    }
+
+    pub fn into_inverted(self) -> MultiMap<V, K, S, A>
+    where
+        K: Clone,
+        A: Clone,
+    {
+        let mut inverted = MultiMap::with_capacity_and_hasher_in(
+            self.inner.len(),
+            self.inner.hasher().clone(),
+            self.inner.allocator().clone(),
+        );
+        self.inner.into_iter().for_each(|(k, set)| {
+            set.into_iter().for_each(|v| inverted.insert(v, k.clone()));
+        });
+        inverted
+    }
 }
 
 impl<'a, K, V, S, A, Q> Index<&'a Q> for MultiMap<K, V, S, A>
@@ -543,6 +609,60 @@ where
         });
         self.inner.retain(|_, set| !set.is_empty());
     }
+
+    /// Removes the first `v` from the values of `k`, dropping `k` if it has no values left.
+    pub fn remove_value<Q>(&mut self, k: &Q, v: &V) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+        V: PartialEq,
+    {
+        let Some(values) = self.inner.get_mut(k) else {
+            return false;
+        };
+        let Some(position) = values.iter().position(|value| value == v) else {
+            return false;
+        };
+        values.remove(position);
+        if values.is_empty() {
+            self.inner.remove(k);
+        }
+        true
+    }
+
+    /// How many values `k` has.
+    pub fn len_values<Q>(&self, k: &Q) -> usize
+    where
+        K: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        self.inner.get(k).map_or(0, Vec::len)
+    }
+
+    pub fn values(&self) -> hash_map::Values<'_, K, Vec<V>> {
+        self.inner.values()
+    }
+
+    pub fn flat_values(&self) -> impl Iterator<Item = &'_ V> {
+        self.inner.values().flatten()
+    }
+
+    /// Builds the reverse map, from each value to every key that maps to it.
+    pub fn invert(&self) -> MultiMap<V, K, S, A>
+    where
+        K: Clone,
+        V: Eq + Hash + Clone,
+        A: Clone,
+    {
+        let mut inverted = MultiMap::with_capacity_and_hasher_in(
+            self.inner.len(),
+            self.inner.hasher().clone(),
+            self.inner.allocator().clone(),
+        );
+        self.flat_iter()
+            .for_each(|(k, v)| inverted.insert(v.clone(), k.clone()));
+        inverted
+    }
 }
 
 impl<'a, K, V, S, A, Q> Index<&'a Q> for OrderedMultiMap<K, V, S, A>
@@ -724,6 +844,182 @@ where
     }
 }
 
+/// A [`MultiMap`] that also keeps the reverse mapping, from each value to its keys, up to date.
+#[derive(Clone)]
+pub struct BiMultiMap<K, V, S = DefaultHashBuilder, A: Allocator = Global> {
+    forward: MultiMap<K, V, S, A>,
+    backward: MultiMap<V, K, S, A>,
+}
+
+impl<K, V> Default for BiMultiMap<K, V> {
+    fn default() -> Self {
+        Self {
+            forward: MultiMap::default(),
+            backward: MultiMap::default(),
+        }
+    }
+}
+
+impl<K, V> BiMultiMap<K, V>
+where
+    K: Eq + Hash,
+    V: Eq + Hash,
+{
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl<K, V, S, A> BiMultiMap<K, V, S, A>
+where
+    K: Eq + Hash + Clone,
+    V: Eq + Hash + Clone,
+    S: BuildHasher + Default + Clone,
+    A: Allocator + Default + Clone,
+{
+    pub fn with_hasher_in(hash_builder: S, allocator: A) -> Self {
+        Self {
+            forward: MultiMap::with_hasher_in(hash_builder.clone(), allocator.clone()),
+            backward: MultiMap::with_hasher_in(hash_builder, allocator),
+        }
+    }
+}
+
+impl<K, V, S, A> BiMultiMap<K, V, S, A>
+where
+    K: Eq + Hash + Clone,
+    V: Eq + Hash + Clone,
+    S: BuildHasher + Default + Clone,
+    A: Allocator + Default,
+{
+    pub fn insert(&mut self, k: K, v: V) {
+        self.backward.insert(v.clone(), k.clone());
+        self.forward.insert(k, v);
+    }
+
+    /// Removes the pair `k -> v` from both directions.
+    pub fn remove(&mut self, k: &K, v: &V) -> bool {
+        self.backward.remove_value(v, k);
+        self.forward.remove_value(k, v)
+    }
+
+    pub fn remove_key(&mut self, k: &K) -> Option<HashSet<V, S, A>> {
+        let values = self.forward.remove(k)?;
+        values.iter().for_each(|v| {
+            self.backward.remove_value(v, k);
+        });
+        Some(values)
+    }
+
+    pub fn remove_value(&mut self, v: &V) -> Option<HashSet<K, S, A>> {
+        let keys = self.backward.remove(v)?;
+        keys.iter().for_each(|k| {
+            self.forward.remove_value(k, v);
+        });
+        Some(keys)
+    }
+
+    pub fn get_by_key(&self, k: &K) -> Option<&HashSet<V, S, A>> {
+        self.forward.get_all(k)
+    }
+
+    pub fn get_by_value(&self, v: &V) -> Option<&HashSet<K, S, A>> {
+        self.backward.get_all(v)
+    }
+
+    pub fn contains_key(&self, k: &K) -> bool {
+        self.forward.contains_key(k)
+    }
+
+    pub fn contains_value(&self, v: &V) -> bool {
+        self.backward.contains_key(v)
+    }
+
+    pub fn len(&self) -> usize {
+        self.forward.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.forward.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.forward.clear();
+        self.backward.clear();
+    }
+
+    pub fn forward(&self) -> &MultiMap<K, V, S, A> {
+        &self.forward
+    }
+
+    pub fn backward(&self) -> &MultiMap<V, K, S, A> {
+        &self.backward
+    }
+
+    pub fn flat_iter(&self) -> impl Iterator<Item = (&'_ K, &'_ V)> {
+        self.forward.flat_iter()
+    }
+}
+
+impl<K, V, S, A> Debug for BiMultiMap<K, V, S, A>
+where
+    K: Eq + Hash + Debug,
+    V: Eq + Hash + Debug,
+    S: BuildHasher + Default + Clone,
+    A: Allocator + Default,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.forward.fmt(f)
+    }
+}
+
+impl<K, V, S, A> PartialEq for BiMultiMap<K, V, S, A>
+where
+    K: Eq + Hash,
+    V: Eq + Hash,
+    S: BuildHasher + Default + Clone,
+    A: Allocator + Default,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.forward == other.forward
+    }
+}
+
+impl<K, V, S, A> Eq for BiMultiMap<K, V, S, A>
+where
+    K: Eq + Hash,
+    V: Eq + Hash,
+    S: BuildHasher + Default + Clone,
+    A: Allocator + Default,
+{
+}
+
+impl<K, V, S, A> FromIterator<(K, V)> for BiMultiMap<K, V, S, A>
+where
+    K: Eq + Hash + Clone,
+    V: Eq + Hash + Clone,
+    S: BuildHasher + Default + Clone,
+    A: Allocator + Default + Clone,
+{
+    fn from_iter<T: IntoIterator<Item = (K, V)>>(iter: T) -> Self {
+        let mut this = Self::with_hasher_in(Default::default(), Default::default());
+        this.extend(iter);
+        this
+    }
+}
+
+impl<K, V, S, A> Extend<(K, V)> for BiMultiMap<K, V, S, A>
+where
+    K: Eq + Hash + Clone,
+    V: Eq + Hash + Clone,
+    S: BuildHasher + Default + Clone,
+    A: Allocator + Default,
+{
+    fn extend<T: IntoIterator<Item = (K, V)>>(&mut self, iter: T) {
+        iter.into_iter().for_each(|(k, v)| self.insert(k, v));
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -939,4 +1235,94 @@ mod tests {
         assert!(foobar_vals.contains(&678));
         assert!(foobar_vals.contains(&789));
     }
+
+    #[test]
+    fn remove_value() {
+        let mut m: MultiMap<usize, usize> = MultiMap::from_iter([(1, 2), (1, 3), (2, 3)]);
+        assert!(m.remove_value(&1, &2));
+        assert!(!m.remove_value(&1, &2));
+        assert_eq!(m.len_values(&1), 1);
+        assert!(m.remove_value(&2, &3));
+        assert!(!m.contains_key(&2));
+
+        let mut m: OrderedMultiMap<usize, usize> =
+            OrderedMultiMap::from_iter([(1, 2), (1, 3), (1, 2)]);
+        assert!(m.remove_value(&1, &2));
+        assert_eq!(m.get_all(&1), Some(&[3, 2][..]));
+        assert_eq!(m.len_values(&1), 2);
+        assert_eq!(m.len_values(&5), 0);
+    }
+
+    #[test]
+    fn values() {
+        let m: MultiMap<usize, usize> = MultiMap::from_iter([(1, 2), (1, 3), (2, 3)]);
+        assert_eq!(m.values().count(), 2);
+        let mut values = m.flat_values().copied().collect::<Vec<_>>();
+        values.sort_unstable();
+        assert_eq!(values, vec![2, 3, 3]);
+
+        let m: OrderedMultiMap<usize, usize> = OrderedMultiMap::from_iter([(1, 2), (1, 2)]);
+        assert_eq!(m.flat_values().count(), 2);
+    }
+
+    #[test]
+    fn invert() {
+        let m: MultiMap<&str, usize> = MultiMap::from_iter([("a", 1), ("a", 2), ("b", 2)]);
+        let inverted = m.invert();
+        assert_eq!(inverted.get_all(&1), Some(&HashSet::from_iter(["a"])));
+        assert_eq!(inverted.get_all(&2), Some(&HashSet::from_iter(["a", "b"])));
+        assert_eq!(inverted, m.clone().into_inverted());
+        assert_eq!(inverted.invert(), m);
+
+        let m: OrderedMultiMap<&str, usize> = OrderedMultiMap::from_iter([("a", 1), ("b", 1)]);
+        assert_eq!(
+            m.invert().get_all(&1),
+            Some(&HashSet::from_iter(["a", "b"]))
+        );
+    }
+
+    /// A hasher whose state can be told apart from its default.
+    #[derive(Debug, Clone, Default, PartialEq)]
+    struct Seeded(u64);
+
+    impl BuildHasher for Seeded {
+        type Hasher = std::hash::DefaultHasher;
+
+        fn build_hasher(&self) -> Self::Hasher {
+            let mut hasher = std::hash::DefaultHasher::new();
+            std::hash::Hasher::write_u64(&mut hasher, self.0);
+            hasher
+        }
+    }
+
+    #[test]
+    fn invert_keeps_hasher() {
+        let mut m: MultiMap<&str, usize, Seeded> = MultiMap::with_hasher_in(Seeded(7), Global);
+        m.insert("a", 1);
+        assert_eq!(m.invert().inner.hasher(), &Seeded(7));
+        assert_eq!(m.into_inverted().inner.hasher(), &Seeded(7));
+
+        let mut m: OrderedMultiMap<&str, usize, Seeded> =
+            OrderedMultiMap::with_hasher_in(Seeded(7), Global);
+        m.insert("a", 1);
+        assert_eq!(m.invert().inner.hasher(), &Seeded(7));
+    }
+
+    #[test]
+    fn bimultimap() {
+        let mut m: BiMultiMap<&str, usize> = BiMultiMap::from_iter([("a", 1), ("a", 2), ("b", 2)]);
+        assert_eq!(m.len(), 3);
+        assert_eq!(m.get_by_value(&2), Some(&HashSet::from_iter(["a", "b"])));
+
+        assert!(m.remove(&"a", &2));
+        assert_eq!(m.get_by_value(&2), Some(&HashSet::from_iter(["b"])));
+
+        assert_eq!(m.remove_value(&2), Some(HashSet::from_iter(["b"])));
+        assert!(!m.contains_key(&"b"));
+
+        assert_eq!(m.remove_key(&"a"), Some(HashSet::from_iter([1])));
+        assert!(!m.contains_value(&1));
+        assert!(m.is_empty());
+        assert!(m.backward().is_empty());
+    }
 }
@@ -1,7 +1,13 @@
-use std::hash::{BuildHasher, Hash};
+use std::{
+    borrow::Borrow,
+    hash::{BuildHasher, Hash},
+    ops::{Add, BitAnd, BitOr, Index, Sub},
+};
 
 use allocator_api2::alloc::{Allocator, Global};
-use hashbrown::{DefaultHashBuilder, HashMap};
+use hashbrown::{hash_map, DefaultHashBuilder, HashMap};
+use itertools::Itertools;
+use rayon::iter::{FromParallelIterator, IntoParallelIterator, ParallelExtend, ParallelIterator};
 
 #[derive(Default, Clone)]
 pub struct HashMultiSet<T, S = DefaultHashBuilder, A = Global>
@@ -66,17 +72,162 @@ where
         *self.inner.entry(v).or_default() += c;
     }
 
-    pub fn contains(&self, v: &T) -> bool {
+    pub fn contains<Q>(&self, v: &Q) -> bool
+    where
+        T: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
         self.inner.contains_key(v)
     }
 
+    pub fn count<Q>(&self, v: &Q) -> usize
+    where
+        T: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.inner.get(v).copied().unwrap_or(0)
+    }
+
+    /// Removes a single occurrence of `v`, returning whether there was one to remove.
+    pub fn remove<Q>(&mut self, v: &Q) -> bool
+    where
+        T: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.remove_many(v, 1) > 0
+    }
+
+    /// Removes up to `c` occurrences of `v`, returning how many were removed.
+    pub fn remove_many<Q>(&mut self, v: &Q, c: usize) -> usize
+    where
+        T: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let Some(count) = self.inner.get_mut(v) else {
+            return 0;
+        };
+        let removed = c.min(*count);
+        *count -= removed;
+        if *count == 0 {
+            self.inner.remove(v);
+        }
+        removed
+    }
+
+    /// Removes every occurrence of `v`, returning how many there were.
+    pub fn remove_all<Q>(&mut self, v: &Q) -> usize
+    where
+        T: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.inner.remove(v).unwrap_or(0)
+    }
+
     pub fn len(&self) -> usize {
         self.inner.iter().map(|(_, v)| v).sum()
     }
 
+    /// Number of distinct values in the set.
+    pub fn distinct_len(&self) -> usize {
+        self.inner.len()
+    }
+
     pub fn is_empty(&self) -> bool {
         self.inner.is_empty()
     }
+
+    pub fn clear(&mut self) {
+        self.inner.clear();
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&T, usize)> {
+        self.inner.iter().map(|(v, &c)| (v, c))
+    }
+
+    pub fn distinct(&self) -> hash_map::Keys<'_, T, usize> {
+        self.inner.keys()
+    }
+
+    /// The `k` most common values, most common first. Ties are broken arbitrarily.
+    pub fn most_common(&self, k: usize) -> Vec<(&T, usize)> {
+        self.iter().k_largest_by_key(k, |&(_, c)| c).collect()
+    }
+
+    pub fn retain<F>(&mut self, mut f: F)
+    where
+        F: FnMut(&T, usize) -> bool,
+    {
+        self.inner.retain(|v, c| f(v, *c));
+    }
+
+    pub fn is_subset(&self, other: &Self) -> bool {
+        self.iter().all(|(v, c)| c <= other.count(v))
+    }
+
+    /// Multiset union, keeping the larger count of each value.
+    pub fn union(&self, other: &Self) -> Self
+    where
+        T: Clone,
+    {
+        self.combine(other, usize::max)
+    }
+
+    /// Multiset intersection, keeping the smaller count of each value.
+    pub fn intersection(&self, other: &Self) -> Self
+    where
+        T: Clone,
+    {
+        self.combine(other, usize::min)
+    }
+
+    /// Multiset difference, subtracting the counts in `other` from this one.
+    pub fn difference(&self, other: &Self) -> Self
+    where
+        T: Clone,
+    {
+        self.combine(other, usize::saturating_sub)
+    }
+
+    /// Multiset sum, adding the counts of each value.
+    pub fn sum(&self, other: &Self) -> Self
+    where
+        T: Clone,
+    {
+        self.combine(other, |a, b| a + b)
+    }
+
+    fn combine(&self, other: &Self, f: impl Fn(usize, usize) -> usize) -> Self
+    where
+        T: Clone,
+    {
+        self.distinct()
+            .chain(other.distinct().filter(|v| !self.contains(*v)))
+            .map(|v| (v.clone(), f(self.count(v), other.count(v))))
+            .filter(|&(_, c)| c > 0)
+            .collect()
+    }
+
+    fn merge(mut self, other: Self) -> Self {
+        if self.inner.len() < other.inner.len() {
+            return other.merge(self);
+        }
+        self.extend(other);
+        self
+    }
+}
+
+impl<T, S, A, Q> Index<&Q> for HashMultiSet<T, S, A>
+where
+    T: Eq + Hash + Borrow<Q>,
+    Q: Eq + Hash + ?Sized,
+    S: BuildHasher,
+    A: Allocator,
+{
+    type Output = usize;
+
+    fn index(&self, index: &Q) -> &Self::Output {
+        self.inner.get(index).unwrap_or(&0)
+    }
 }
 
 impl<T, S, A> IntoIterator for HashMultiSet<T, S, A>
@@ -140,3 +291,184 @@ where
         iter.into_iter().for_each(|v| self.insert(v));
     }
 }
+
+impl<T, S, A> BitOr<&HashMultiSet<T, S, A>> for &HashMultiSet<T, S, A>
+where
+    T: Eq + Hash + Clone,
+    S: Default + BuildHasher,
+    A: Default + Allocator,
+{
+    type Output = HashMultiSet<T, S, A>;
+
+    fn bitor(self, rhs: &HashMultiSet<T, S, A>) -> Self::Output {
+        self.union(rhs)
+    }
+}
+
+impl<T, S, A> BitAnd<&HashMultiSet<T, S, A>> for &HashMultiSet<T, S, A>
+where
+    T: Eq + Hash + Clone,
+    S: Default + BuildHasher,
+    A: Default + Allocator,
+{
+    type Output = HashMultiSet<T, S, A>;
+
+    fn bitand(self, rhs: &HashMultiSet<T, S, A>) -> Self::Output {
+        self.intersection(rhs)
+    }
+}
+
+impl<T, S, A> Sub<&HashMultiSet<T, S, A>> for &HashMultiSet<T, S, A>
+where
+    T: Eq + Hash + Clone,
+    S: Default + BuildHasher,
+    A: Default + Allocator,
+{
+    type Output = HashMultiSet<T, S, A>;
+
+    fn sub(self, rhs: &HashMultiSet<T, S, A>) -> Self::Output {
+        self.difference(rhs)
+    }
+}
+
+impl<T, S, A> Add<&HashMultiSet<T, S, A>> for &HashMultiSet<T, S, A>
+where
+    T: Eq + Hash + Clone,
+    S: Default + BuildHasher,
+    A: Default + Allocator,
+{
+    type Output = HashMultiSet<T, S, A>;
+
+    fn add(self, rhs: &HashMultiSet<T, S, A>) -> Self::Output {
+        self.sum(rhs)
+    }
+}
+
+impl<T, S, A> FromParallelIterator<T> for HashMultiSet<T, S, A>
+where
+    T: Eq + Hash + Send,
+    S: BuildHasher + Default + Send,
+    A: Allocator + Default + Send,
+{
+    fn from_par_iter<I>(par_iter: I) -> Self
+    where
+        I: IntoParallelIterator<Item = T>,
+    {
+        par_iter
+            .into_par_iter()
+            .fold(Self::new, |mut this, v| {
+                this.insert(v);
+                this
+            })
+            .reduce(Self::new, Self::merge)
+    }
+}
+
+impl<T, S, A> FromParallelIterator<(T, usize)> for HashMultiSet<T, S, A>
+where
+    T: Eq + Hash + Send,
+    S: BuildHasher + Default + Send,
+    A: Allocator + Default + Send,
+{
+    fn from_par_iter<I>(par_iter: I) -> Self
+    where
+        I: IntoParallelIterator<Item = (T, usize)>,
+    {
+        par_iter
+            .into_par_iter()
+            .fold(Self::new, |mut this, (v, c)| {
+                this.insert_many(v, c);
+                this
+            })
+            .reduce(Self::new, Self::merge)
+    }
+}
+
+impl<T, S, A> ParallelExtend<T> for HashMultiSet<T, S, A>
+where
+    T: Eq + Hash + Send,
+    S: BuildHasher + Default + Send,
+    A: Allocator + Default + Send,
+{
+    fn par_extend<I>(&mut self, par_iter: I)
+    where
+        I: IntoParallelIterator<Item = T>,
+    {
+        let other: Self = par_iter.into_par_iter().collect();
+        self.extend(other);
+    }
+}
+
+impl<T, S, A> ParallelExtend<(T, usize)> for HashMultiSet<T, S, A>
+where
+    T: Eq + Hash + Send,
+    S: BuildHasher + Default + Send,
+    A: Allocator + Default + Send,
+{
+    fn par_extend<I>(&mut self, par_iter: I)
+    where
+        I: IntoParallelIterator<Item = (T, usize)>,
+    {
+        let other: Self = par_iter.into_par_iter().collect();
+        self.extend(other);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn counts() {
+        let mut set: HashMultiSet<char> = "mississippi".chars().collect();
+        assert_eq!(set.len(), 11);
+        assert_eq!(set.distinct_len(), 4);
+        assert_eq!(set.count(&'s'), 4);
+        assert_eq!(set[&'p'], 2);
+        assert_eq!(set[&'z'], 0);
+
+        assert!(set.remove(&'m'));
+        assert!(!set.remove(&'m'));
+        assert!(!set.contains(&'m'));
+        assert_eq!(set.remove_many(&'s', 3), 3);
+        assert_eq!(set.remove_all(&'i'), 4);
+        assert_eq!(set.len(), 3);
+    }
+
+    #[test]
+    fn most_common() {
+        let set: HashMultiSet<char> = "mississippi".chars().collect();
+        let common = set.most_common(2);
+        assert_eq!(common.len(), 2);
+        assert!(common.iter().all(|&(_, c)| c == 4));
+        assert_eq!(set.most_common(10).len(), 4);
+    }
+
+    #[test]
+    fn set_ops() {
+        let a: HashMultiSet<usize> = [1, 1, 1, 2, 3].into_iter().collect();
+        let b: HashMultiSet<usize> = [1, 2, 2, 4].into_iter().collect();
+
+        let union = &a | &b;
+        assert_eq!((union[&1], union[&2], union[&3], union[&4]), (3, 2, 1, 1));
+        let intersection = &a & &b;
+        assert_eq!(intersection, [1, 2].into_iter().collect());
+        let difference = &a - &b;
+        assert_eq!(difference, [1, 1, 3].into_iter().collect());
+        let sum = &a + &b;
+        assert_eq!(sum.len(), a.len() + b.len());
+        assert!(intersection.is_subset(&a));
+        assert!(!a.is_subset(&b));
+    }
+
+    #[test]
+    fn parallel() {
+        let set: HashMultiSet<usize> = (0..1000usize).into_par_iter().map(|i| i % 7).collect();
+        assert_eq!(set.len(), 1000);
+        assert_eq!(set[&0], 143);
+
+        let mut set = set;
+        set.par_extend((0..7usize).into_par_iter().map(|i| (i, 2)));
+        assert_eq!(set[&6], 144);
+    }
+}
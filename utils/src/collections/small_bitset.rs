@@ -0,0 +1,380 @@
+use std::ops::{
+    BitAnd, BitAndAssign, BitOr, BitOrAssign, BitXor, BitXorAssign, Not, Sub, SubAssign,
+};
+
+use super::bitset::{Dim, FromBitSetIndex, ToBitSetIndex};
+
+/// A fixed-capacity bitset holding `WORDS * 64` bits inline, for use as a search state.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct SmallBitSet<const WORDS: usize = 1> {
+    words: [u64; WORDS],
+}
+
+impl<const WORDS: usize> Default for SmallBitSet<WORDS> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const WORDS: usize> std::fmt::Debug for SmallBitSet<WORDS> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+impl<const WORDS: usize> SmallBitSet<WORDS> {
+    pub const CAPACITY: usize = WORDS * u64::BITS as usize;
+
+    pub const fn new() -> Self {
+        Self { words: [0; WORDS] }
+    }
+
+    pub const fn from_words(words: [u64; WORDS]) -> Self {
+        Self { words }
+    }
+
+    /// The set `{0, 1, ..., n - 1}`.
+    pub fn full(n: usize) -> Self {
+        assert!(
+            n <= Self::CAPACITY,
+            "{n} is past the capacity of SmallBitSet"
+        );
+        let mut this = Self::new();
+        for (i, word) in this.words.iter_mut().enumerate() {
+            let low = i * u64::BITS as usize;
+            *word = match n.saturating_sub(low) {
+                0 => 0,
+                bits if bits >= u64::BITS as usize => u64::MAX,
+                bits => (1 << bits) - 1,
+            };
+        }
+        this
+    }
+
+    pub fn words(&self) -> &[u64; WORDS] {
+        &self.words
+    }
+
+    #[inline]
+    fn split(position: usize) -> (usize, u64) {
+        assert!(
+            position < Self::CAPACITY,
+            "{position} is past the capacity of SmallBitSet"
+        );
+        (
+            position / u64::BITS as usize,
+            1 << (position % u64::BITS as usize),
+        )
+    }
+
+    pub fn insert(&mut self, position: usize) -> bool {
+        let (word, bit) = Self::split(position);
+        let inserted = self.words[word] & bit == 0;
+        self.words[word] |= bit;
+        inserted
+    }
+
+    #[must_use]
+    pub fn with(mut self, position: usize) -> Self {
+        self.insert(position);
+        self
+    }
+
+    pub fn remove(&mut self, position: usize) -> bool {
+        let (word, bit) = Self::split(position);
+        let removed = self.words[word] & bit != 0;
+        self.words[word] &= !bit;
+        removed
+    }
+
+    #[must_use]
+    pub fn without(mut self, position: usize) -> Self {
+        self.remove(position);
+        self
+    }
+
+    pub fn contains(&self, position: usize) -> bool {
+        let (word, bit) = Self::split(position);
+        self.words[word] & bit != 0
+    }
+
+    pub fn insert_key<K: ToBitSetIndex>(&mut self, key: &K) -> bool {
+        self.insert(key.to_bitset_index(&Dim::Unbounded))
+    }
+
+    pub fn contains_key<K: ToBitSetIndex>(&self, key: &K) -> bool {
+        self.contains(key.to_bitset_index(&Dim::Unbounded))
+    }
+
+    pub fn len(&self) -> usize {
+        self.words.iter().map(|w| w.count_ones() as usize).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.words.iter().all(|&w| w == 0)
+    }
+
+    pub fn clear(&mut self) {
+        self.words = [0; WORDS];
+    }
+
+    pub fn first(&self) -> Option<usize> {
+        self.iter().next()
+    }
+
+    pub fn is_subset(&self, other: &Self) -> bool {
+        self.words
+            .iter()
+            .zip(&other.words)
+            .all(|(a, b)| a & !b == 0)
+    }
+
+    pub fn is_superset(&self, other: &Self) -> bool {
+        other.is_subset(self)
+    }
+
+    pub fn is_disjoint(&self, other: &Self) -> bool {
+        self.words.iter().zip(&other.words).all(|(a, b)| a & b == 0)
+    }
+
+    /// Complement relative to the universe `{0, 1, ..., n - 1}`.
+    #[must_use]
+    pub fn complement(&self, n: usize) -> Self {
+        !*self & Self::full(n)
+    }
+
+    pub fn iter(&self) -> Iter<WORDS> {
+        Iter {
+            words: self.words,
+            word: 0,
+        }
+    }
+
+    pub fn keys<K: FromBitSetIndex>(&self) -> impl Iterator<Item = K> {
+        self.iter()
+            .map(|index| K::from_bitset_index(index, &Dim::Unbounded))
+    }
+
+    /// Every subset of this set, starting at the set itself and ending with the empty set.
+    pub fn subsets(&self) -> Subsets<WORDS> {
+        Subsets {
+            mask: *self,
+            next: Some(*self),
+        }
+    }
+
+    fn decrement(&mut self) {
+        for word in &mut self.words {
+            let (value, borrow) = word.overflowing_sub(1);
+            *word = value;
+            if !borrow {
+                break;
+            }
+        }
+    }
+}
+
+impl SmallBitSet<1> {
+    /// The raw mask, for indexing DP tables over subsets.
+    pub fn as_index(&self) -> usize {
+        self.words[0] as usize
+    }
+}
+
+impl ToBitSetIndex for SmallBitSet<1> {
+    fn to_bitset_index(&self, _dim: &Dim) -> usize {
+        self.as_index()
+    }
+}
+
+impl FromBitSetIndex for SmallBitSet<1> {
+    fn from_bitset_index(index: usize, _dim: &Dim) -> Self {
+        Self::from_words([index as u64])
+    }
+}
+
+pub struct Iter<const WORDS: usize> {
+    words: [u64; WORDS],
+    word: usize,
+}
+
+impl<const WORDS: usize> Iterator for Iter<WORDS> {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.word < WORDS {
+            let w = &mut self.words[self.word];
+            if *w != 0 {
+                let bit = w.trailing_zeros() as usize;
+                *w &= *w - 1;
+                return Some(self.word * u64::BITS as usize + bit);
+            }
+            self.word += 1;
+        }
+        None
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.words[self.word.min(WORDS)..]
+            .iter()
+            .map(|w| w.count_ones() as usize)
+            .sum();
+        (len, Some(len))
+    }
+}
+
+impl<const WORDS: usize> ExactSizeIterator for Iter<WORDS> {}
+
+pub struct Subsets<const WORDS: usize> {
+    mask: SmallBitSet<WORDS>,
+    next: Option<SmallBitSet<WORDS>>,
+}
+
+impl<const WORDS: usize> Iterator for Subsets<WORDS> {
+    type Item = SmallBitSet<WORDS>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let current = self.next?;
+        self.next = (!current.is_empty()).then(|| {
+            let mut next = current;
+            next.decrement();
+            next & self.mask
+        });
+        Some(current)
+    }
+}
+
+impl<const WORDS: usize> IntoIterator for SmallBitSet<WORDS> {
+    type Item = usize;
+    type IntoIter = Iter<WORDS>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<const WORDS: usize> IntoIterator for &SmallBitSet<WORDS> {
+    type Item = usize;
+    type IntoIter = Iter<WORDS>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<const WORDS: usize> FromIterator<usize> for SmallBitSet<WORDS> {
+    fn from_iter<T: IntoIterator<Item = usize>>(iter: T) -> Self {
+        let mut this = Self::new();
+        this.extend(iter);
+        this
+    }
+}
+
+impl<const WORDS: usize> Extend<usize> for SmallBitSet<WORDS> {
+    fn extend<T: IntoIterator<Item = usize>>(&mut self, iter: T) {
+        iter.into_iter().for_each(|i| {
+            self.insert(i);
+        });
+    }
+}
+
+impl<const WORDS: usize> Not for SmallBitSet<WORDS> {
+    type Output = Self;
+
+    fn not(mut self) -> Self::Output {
+        self.words.iter_mut().for_each(|w| *w = !*w);
+        self
+    }
+}
+
+macro_rules! bit_op {
+    ($trait:ident, $method:ident, $assign_trait:ident, $assign_method:ident, |$a:ident, $b:ident| $op:expr) => {
+        impl<const WORDS: usize> $assign_trait for SmallBitSet<WORDS> {
+            fn $assign_method(&mut self, rhs: Self) {
+                self.words
+                    .iter_mut()
+                    .zip(rhs.words)
+                    .for_each(|($a, $b)| *$a = $op);
+            }
+        }
+
+        impl<const WORDS: usize> $trait for SmallBitSet<WORDS> {
+            type Output = Self;
+
+            fn $method(mut self, rhs: Self) -> Self::Output {
+                self.$assign_method(rhs);
+                self
+            }
+        }
+    };
+}
+
+bit_op!(BitOr, bitor, BitOrAssign, bitor_assign, |a, b| *a | b);
+bit_op!(BitAnd, bitand, BitAndAssign, bitand_assign, |a, b| *a & b);
+bit_op!(Sub, sub, SubAssign, sub_assign, |a, b| *a & !b);
+bit_op!(BitXor, bitxor, BitXorAssign, bitxor_assign, |a, b| *a ^ b);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn insert_contains() {
+        let mut set = SmallBitSet::<2>::new();
+        assert!(set.insert(3));
+        assert!(!set.insert(3));
+        assert!(set.insert(100));
+        assert!(set.contains(3));
+        assert!(set.contains(100));
+        assert!(!set.contains(64));
+        assert_eq!(set.len(), 2);
+        assert_eq!(set.iter().collect::<Vec<_>>(), vec![3, 100]);
+        assert!(set.remove(3));
+        assert_eq!(set.first(), Some(100));
+    }
+
+    #[test]
+    fn set_ops() {
+        let a: SmallBitSet = [1, 2, 3].into_iter().collect();
+        let b: SmallBitSet = [3, 4].into_iter().collect();
+        assert_eq!((a | b).iter().collect::<Vec<_>>(), vec![1, 2, 3, 4]);
+        assert_eq!((a & b).iter().collect::<Vec<_>>(), vec![3]);
+        assert_eq!((a - b).iter().collect::<Vec<_>>(), vec![1, 2]);
+        assert_eq!((a ^ b).iter().collect::<Vec<_>>(), vec![1, 2, 4]);
+        assert_eq!(a.complement(5).iter().collect::<Vec<_>>(), vec![0, 4]);
+        assert!((a & b).is_subset(&a));
+        assert!(a.is_superset(&(a - b)));
+    }
+
+    #[test]
+    fn full_across_words() {
+        let set = SmallBitSet::<2>::full(70);
+        assert_eq!(set.len(), 70);
+        assert!(set.contains(69));
+        assert!(!set.contains(70));
+        assert_eq!(SmallBitSet::<2>::full(128).len(), 128);
+    }
+
+    #[test]
+    fn subsets() {
+        let set: SmallBitSet = [0, 2, 5].into_iter().collect();
+        let subsets = set.subsets().collect::<Vec<_>>();
+        assert_eq!(subsets.len(), 8);
+        assert_eq!(subsets.first(), Some(&set));
+        assert_eq!(subsets.last(), Some(&SmallBitSet::new()));
+        assert!(subsets.iter().all(|s| s.is_subset(&set)));
+
+        let wide: SmallBitSet<2> = [1, 64, 127].into_iter().collect();
+        assert_eq!(wide.subsets().count(), 8);
+        assert!(wide.subsets().all(|s| s.is_subset(&wide)));
+    }
+
+    #[test]
+    fn index_roundtrip() {
+        let set: SmallBitSet = [0, 3].into_iter().collect();
+        let index = set.to_bitset_index(&Dim::Unbounded);
+        assert_eq!(index, 0b1001);
+        assert_eq!(SmallBitSet::from_bitset_index(index, &Dim::Unbounded), set);
+        assert_eq!(set.keys::<usize>().collect::<Vec<_>>(), vec![0, 3]);
+    }
+}
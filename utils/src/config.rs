@@ -0,0 +1,223 @@
+//! Where inputs are kept, and whose they are.
+//!
+//! Everything is optional. Without an `aoc.toml` in the project root, inputs live in `input/` and
+//! are downloaded with `AOCSESSION`. With one, the input root can move and other accounts can be
+//! added as profiles, each with their own session and inputs:
+//!
+//! ```toml
+//! input_root = "input"
+//! # Sent along with every download, so the site can tell who to contact about it.
+//! contact = "github.com/someone/aoc"
+//!
+//! # Session from AOCSESSION_ALICE, inputs in input/alice/<year>/dayNN.txt.
+//! [profiles.alice]
+//!
+//! [profiles.bob]
+//! input_dir = "/srv/aoc/bob"
+//! session_env = "BOB_SESSION"
+//! ```
+//!
+//! `AOC_INPUT_ROOT` overrides `input_root`, `AOC_USER_AGENT` overrides `contact`, and a year binary
+//! picks a profile with `--profile`.
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+};
+
+use miette::{IntoDiagnostic, Result, WrapErr};
+use serde::Deserialize;
+
+use crate::utils::file::{project_root, Fetcher};
+
+pub const CONFIG_FILE: &str = "aoc.toml";
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ProfileConfig {
+    input_dir: Option<PathBuf>,
+    session_env: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Config {
+    #[serde(skip)]
+    root: PathBuf,
+    input_root: Option<PathBuf>,
+    contact: Option<String>,
+    #[serde(default)]
+    profiles: BTreeMap<String, ProfileConfig>,
+}
+
+impl Config {
+    /// Reads `aoc.toml` from the project root, if there is one.
+    pub fn load() -> Result<Self> {
+        let root = project_root()?;
+        let path = root.join(CONFIG_FILE);
+        let mut config = if path.exists() {
+            let text = std::fs::read_to_string(&path)
+                .into_diagnostic()
+                .wrap_err_with(|| format!("reading {}", path.display()))?;
+            Self::parse(&root, &text).wrap_err_with(|| format!("reading {}", path.display()))?
+        } else {
+            Self::new(&root)
+        };
+        if let Ok(input_root) = std::env::var("AOC_INPUT_ROOT") {
+            config.input_root = Some(input_root.into());
+        }
+        if let Ok(contact) = std::env::var("AOC_USER_AGENT") {
+            config.contact = Some(contact);
+        }
+        Ok(config)
+    }
+
+    /// The defaults, with relative paths taken from `root`.
+    pub fn new(root: &Path) -> Self {
+        Self {
+            root: root.to_path_buf(),
+            ..Self::default()
+        }
+    }
+
+    pub fn parse(root: &Path, text: &str) -> Result<Self> {
+        let config: Self = toml::from_str(text).into_diagnostic()?;
+        Ok(Self {
+            root: root.to_path_buf(),
+            ..config
+        })
+    }
+
+    pub fn input_root(&self) -> PathBuf {
+        self.root
+            .join(self.input_root.as_deref().unwrap_or(Path::new("input")))
+    }
+
+    /// The names of the profiles besides the default one.
+    pub fn profiles(&self) -> impl Iterator<Item = &str> + '_ {
+        self.profiles.keys().map(String::as_str)
+    }
+
+    /// The profile called `name`, or the default one for `None`.
+    pub fn profile(&self, name: Option<&str>) -> Result<Profile> {
+        let Some(name) = name else {
+            return Ok(Profile {
+                name: None,
+                input_dir: self.input_root(),
+                session: std::env::var("AOCSESSION").ok(),
+                contact: self.contact.clone(),
+            });
+        };
+        let Some(profile) = self.profiles.get(name) else {
+            miette::bail!("there's no profile `{name}` in {CONFIG_FILE}");
+        };
+        let input_dir = match &profile.input_dir {
+            Some(dir) => self.root.join(dir),
+            None => self.input_root().join(name),
+        };
+        let session_env = profile
+            .session_env
+            .clone()
+            .unwrap_or_else(|| format!("AOCSESSION_{}", name.to_uppercase().replace('-', "_")));
+        Ok(Profile {
+            name: Some(name.to_string()),
+            input_dir,
+            session: std::env::var(session_env).ok(),
+            contact: self.contact.clone(),
+        })
+    }
+}
+
+/// The profile called `name` in the project's config, or the default one for `None`.
+pub fn profile(name: Option<&str>) -> Result<Profile> {
+    Config::load()?.profile(name)
+}
+
+/// One account's session and inputs.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Profile {
+    /// `None` for the default profile.
+    pub name: Option<String>,
+    pub input_dir: PathBuf,
+    pub session: Option<String>,
+    /// Who to contact about the downloads, for the User-Agent.
+    pub contact: Option<String>,
+}
+
+impl Profile {
+    pub fn name(&self) -> &str {
+        self.name.as_deref().unwrap_or("default")
+    }
+
+    pub fn input_path(&self, year: usize, day: usize) -> PathBuf {
+        self.input_dir
+            .join(year.to_string())
+            .join(format!("day{day:02}.txt"))
+    }
+
+    /// Where the puzzle's page is cached, next to its input.
+    pub fn puzzle_path(&self, year: usize, day: usize) -> PathBuf {
+        self.input_path(year, day).with_extension("html")
+    }
+
+    pub fn fetcher(&self) -> Result<Fetcher> {
+        Fetcher::new(self.session.as_deref())?.with_contact(self.contact.as_deref())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn defaults() {
+        let config = Config::new(Path::new("/aoc"));
+        assert_eq!(config.input_root(), Path::new("/aoc/input"));
+        assert_eq!(config.profiles().count(), 0);
+        let profile = config.profile(None).unwrap();
+        assert_eq!(profile.name(), "default");
+        assert_eq!(
+            profile.input_path(2024, 3),
+            Path::new("/aoc/input/2024/day03.txt")
+        );
+        assert_eq!(
+            profile.puzzle_path(2024, 3),
+            Path::new("/aoc/input/2024/day03.html")
+        );
+        assert!(config.profile(Some("alice")).is_err());
+    }
+
+    #[test]
+    fn profiles() {
+        let config = Config::parse(
+            Path::new("/aoc"),
+            r#"
+                input_root = "inputs"
+                contact = "me@example.com"
+
+                [profiles.alice]
+
+                [profiles.bob-2]
+                input_dir = "/srv/bob"
+                session_env = "AOC_CONFIG_TEST_BOB"
+            "#,
+        )
+        .unwrap();
+        assert_eq!(config.profiles().collect::<Vec<_>>(), ["alice", "bob-2"]);
+
+        std::env::set_var("AOCSESSION_ALICE", "alice's");
+        let alice = config.profile(Some("alice")).unwrap();
+        assert_eq!(alice.input_dir, Path::new("/aoc/inputs/alice"));
+        assert_eq!(alice.session.as_deref(), Some("alice's"));
+        assert_eq!(alice.contact.as_deref(), Some("me@example.com"));
+
+        std::env::set_var("AOC_CONFIG_TEST_BOB", "bob's");
+        let bob = config.profile(Some("bob-2")).unwrap();
+        assert_eq!(
+            bob.input_path(2019, 25),
+            Path::new("/srv/bob/2019/day25.txt")
+        );
+        assert_eq!(bob.session.as_deref(), Some("bob's"));
+
+        assert!(Config::parse(Path::new("/aoc"), "input_rot = \"x\"").is_err());
+    }
+}
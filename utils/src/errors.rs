@@ -1,8 +1,8 @@
 use std::fmt::{Debug, Display};
 
-use miette::miette;
+use miette::SourceSpan;
 use winnow::{
-    error::{ParseError, ParserError},
+    error::{ContextError, ParseError, ParserError, StrContext},
     stream::{AsBStr, Stream},
 };
 
@@ -12,6 +12,57 @@ pub enum Error {
     InvalidInput(String),
 }
 
+/// A parse failure that keeps the original input around, so that miette can point at the
+/// offending spot in the terminal.
+#[derive(Debug, thiserror::Error, miette::Diagnostic)]
+#[error("failed to parse input")]
+#[diagnostic(code(aoc_utils::parse))]
+pub struct ParseDiagnostic {
+    #[source_code]
+    src: String,
+
+    #[label("{label}")]
+    span: SourceSpan,
+
+    label: String,
+
+    #[help]
+    help: Option<String>,
+}
+
+impl ParseDiagnostic {
+    pub fn span(&self) -> SourceSpan {
+        self.span
+    }
+
+    pub fn label(&self) -> &str {
+        &self.label
+    }
+}
+
+/// Extracts the `StrContext::Label`s a winnow error was wrapped in, innermost first. Every error
+/// type has it, but only `ContextError<StrContext>` has anything to say.
+pub trait ContextLabels {
+    fn labels(&self) -> Vec<&'static str>;
+}
+
+impl<C> ContextLabels for C {
+    default fn labels(&self) -> Vec<&'static str> {
+        Vec::new()
+    }
+}
+
+impl ContextLabels for ContextError<StrContext> {
+    fn labels(&self) -> Vec<&'static str> {
+        self.context()
+            .filter_map(|c| match c {
+                StrContext::Label(l) => Some(*l),
+                _ => None,
+            })
+            .collect()
+    }
+}
+
 #[allow(dead_code)]
 pub trait ToMiette<O> {
     fn to_miette(self) -> Result<O, miette::Report>;
@@ -36,6 +87,105 @@ where
     C: ParserError<S> + Display + Debug,
 {
     fn to_miette(self) -> miette::Report {
-        miette!("{self}")
+        let src = String::from_utf8_lossy(self.input().as_bstr()).into_owned();
+        let offset = self.offset().min(src.len());
+        let len = src
+            .get(offset..)
+            .and_then(|rest| rest.chars().next())
+            .map_or(0, char::len_utf8);
+
+        let inner = self.inner().to_string();
+        let label = if inner.is_empty() {
+            "here".to_string()
+        } else {
+            inner.lines().collect::<Vec<_>>().join(", ")
+        };
+        let outer = self
+            .inner()
+            .labels()
+            .into_iter()
+            .skip(1)
+            .collect::<Vec<_>>();
+        let help = (!outer.is_empty()).then(|| format!("while parsing {}", outer.join(" in ")));
+
+        ParseDiagnostic {
+            src,
+            span: (offset, len).into(),
+            label,
+            help,
+        }
+        .into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use winnow::{
+        ascii::dec_uint,
+        combinator::preceded,
+        error::{ErrorKind, InputError},
+        PResult, Parser,
+    };
+
+    use super::*;
+
+    fn assignment(input: &mut &str) -> PResult<usize> {
+        preceded("a=", dec_uint.context(StrContext::Label("number")))
+            .context(StrContext::Label("assignment"))
+            .parse_next(input)
+    }
+
+    #[test]
+    fn parse_error_span() {
+        let err = assignment.parse("a=x").unwrap_err().to_miette();
+        let diagnostic = err.downcast_ref::<ParseDiagnostic>().unwrap();
+        assert_eq!(diagnostic.span(), (2, 1).into());
+        assert_eq!(diagnostic.label(), "invalid number");
+        assert_eq!(diagnostic.help.as_deref(), Some("while parsing assignment"));
+    }
+
+    /// A parser's own error type, which has no labels to give.
+    #[derive(Debug)]
+    struct Kind(ErrorKind);
+
+    impl Display for Kind {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            write!(f, "{:?}", self.0)
+        }
+    }
+
+    impl<I: Stream> ParserError<I> for Kind {
+        fn from_error_kind(_: &I, kind: ErrorKind) -> Self {
+            Self(kind)
+        }
+
+        fn append(self, _: &I, _: &I::Checkpoint, _: ErrorKind) -> Self {
+            self
+        }
+    }
+
+    #[test]
+    fn other_error_types() {
+        let err = dec_uint::<_, usize, Kind>
+            .parse("x")
+            .unwrap_err()
+            .to_miette();
+        let diagnostic = err.downcast_ref::<ParseDiagnostic>().unwrap();
+        assert_eq!(diagnostic.span(), (0, 1).into());
+        assert_eq!(diagnostic.label(), "Verify");
+        assert_eq!(diagnostic.help, None);
+
+        let err = dec_uint::<_, usize, InputError<&str>>
+            .parse("x")
+            .unwrap_err()
+            .to_miette();
+        assert!(err.downcast_ref::<ParseDiagnostic>().is_some());
+    }
+
+    #[test]
+    fn parse_error_at_end() {
+        let err = assignment.parse("a=").unwrap_err().to_miette();
+        let diagnostic = err.downcast_ref::<ParseDiagnostic>().unwrap();
+        assert_eq!(diagnostic.span(), (2, 0).into());
     }
 }
@@ -0,0 +1,369 @@
+//! Private leaderboards, read from the JSON the site serves at
+//! `/<year>/leaderboard/private/view/<id>.json`.
+//!
+//! The site asks for that JSON to be downloaded at most once every 15 minutes, so [`load`] keeps a
+//! copy next to the inputs and only downloads it again once the copy is older than [`REFRESH`].
+use std::{
+    collections::BTreeMap,
+    fmt::{self, Display},
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use chrono::{DateTime, TimeZone, Utc};
+use miette::{IntoDiagnostic, Result, WrapErr};
+use serde::Deserialize;
+
+use crate::{
+    config::Profile,
+    utils::file::{load_or_fetch, Fetch},
+};
+
+pub const REFRESH: Duration = Duration::from_secs(15 * 60);
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct Star {
+    /// When the star was earned, in seconds since the epoch.
+    pub get_star_ts: i64,
+    /// The order the site handed out stars in, across everyone.
+    #[serde(default)]
+    pub star_index: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct Member {
+    pub id: u64,
+    /// `None` for members who stay anonymous.
+    pub name: Option<String>,
+    pub stars: usize,
+    pub local_score: u64,
+    #[serde(default)]
+    pub global_score: u64,
+    pub last_star_ts: i64,
+    /// The stars by day, then part.
+    #[serde(default)]
+    pub completion_day_level: BTreeMap<usize, BTreeMap<usize, Star>>,
+}
+
+impl Member {
+    /// The name, or what the site shows for anonymous members.
+    pub fn name(&self) -> String {
+        self.name
+            .clone()
+            .unwrap_or_else(|| format!("(anonymous user #{})", self.id))
+    }
+
+    pub fn star(&self, day: usize, part: usize) -> Option<DateTime<Utc>> {
+        let star = self.completion_day_level.get(&day)?.get(&part)?;
+        DateTime::from_timestamp(star.get_star_ts, 0)
+    }
+
+    /// How long part 2 took after part 1.
+    pub fn delta(&self, day: usize) -> Option<Duration> {
+        (self.star(day, 2)? - self.star(day, 1)?).to_std().ok()
+    }
+
+    /// How long after the puzzle unlocked `part` was solved.
+    pub fn solve_time(&self, year: i32, day: usize, part: usize) -> Option<Duration> {
+        (self.star(day, part)? - unlock(year, day)).to_std().ok()
+    }
+}
+
+/// When the puzzle for `day` unlocks: midnight in New York. Panics unless `day` is in `1..=31`;
+/// [`Leaderboard::day`] only takes the puzzle days, `1..=25`.
+pub fn unlock(year: i32, day: usize) -> DateTime<Utc> {
+    Utc.with_ymd_and_hms(year, 12, day as u32, 5, 0, 0)
+        .single()
+        .expect("days are in December")
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct Leaderboard {
+    pub event: String,
+    pub owner_id: u64,
+    pub members: BTreeMap<u64, Member>,
+}
+
+impl Leaderboard {
+    pub fn parse(json: &str) -> Result<Self> {
+        serde_json::from_str(json)
+            .into_diagnostic()
+            .wrap_err("reading leaderboard JSON")
+    }
+
+    pub fn from_file(path: &Path) -> Result<Self> {
+        let json = std::fs::read_to_string(path)
+            .into_diagnostic()
+            .wrap_err_with(|| format!("reading {}", path.display()))?;
+        Self::parse(&json)
+    }
+
+    pub fn year(&self) -> Result<i32> {
+        self.event
+            .parse()
+            .into_diagnostic()
+            .wrap_err_with(|| format!("event `{}` isn't a year", self.event))
+    }
+
+    /// Members by local score, then stars, then who got their last star first.
+    pub fn rankings(&self) -> Vec<&Member> {
+        let mut members = self.members.values().collect::<Vec<_>>();
+        members.sort_by_key(|m| (std::cmp::Reverse((m.local_score, m.stars)), m.last_star_ts));
+        members
+    }
+
+    pub fn standings(&self) -> Standings<'_> {
+        Standings(self)
+    }
+
+    /// Who solved `day` and when, in the time zone `tz`.
+    pub fn day<Tz: TimeZone>(&self, day: usize, tz: Tz) -> Result<DayTable<'_, Tz>> {
+        if !(1..=25).contains(&day) {
+            miette::bail!("there's no day {day}, puzzles run from day 1 to day 25");
+        }
+        Ok(DayTable {
+            leaderboard: self,
+            year: self.year()?,
+            day,
+            tz,
+        })
+    }
+}
+
+/// `1:02:03`, or `2d 01:02:03` once it's over a day.
+fn hms(time: Duration) -> String {
+    let secs = time.as_secs();
+    let (days, h, m, s) = (secs / 86400, secs / 3600 % 24, secs / 60 % 60, secs % 60);
+    if days > 0 {
+        format!("{days}d {h:02}:{m:02}:{s:02}")
+    } else {
+        format!("{h}:{m:02}:{s:02}")
+    }
+}
+
+/// The rankings, with a `*` for every day a member finished and a `.` for every day they're half
+/// way through.
+pub struct Standings<'a>(&'a Leaderboard);
+
+impl Display for Standings<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "{:>4} {:>6} {:>6}  {:<25}  name",
+            "rank", "score", "stars", "days"
+        )?;
+        for (rank, member) in self.0.rankings().into_iter().enumerate() {
+            let days = (1..=25)
+                .map(
+                    |day| match member.completion_day_level.get(&day).map(BTreeMap::len) {
+                        Some(2) => '*',
+                        Some(1) => '.',
+                        _ => ' ',
+                    },
+                )
+                .collect::<String>();
+            writeln!(
+                f,
+                "{:>4} {:>6} {:>6}  {days}  {}",
+                rank + 1,
+                member.local_score,
+                member.stars,
+                member.name()
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// When everyone who got a star on one day got them, in some time zone, and how long part 2 took.
+pub struct DayTable<'a, Tz: TimeZone> {
+    leaderboard: &'a Leaderboard,
+    year: i32,
+    day: usize,
+    tz: Tz,
+}
+
+impl<Tz: TimeZone> DayTable<'_, Tz>
+where
+    Tz::Offset: Display,
+{
+    /// When `member` got the star for `part`, and how long after the unlock.
+    fn cell(&self, member: &Member, part: usize) -> String {
+        let Some(star) = member.star(self.day, part) else {
+            return format!("{:<31}", "-");
+        };
+        let taken = member
+            .solve_time(self.year, self.day, part)
+            .map_or_else(String::new, hms);
+        let star = star.with_timezone(&self.tz);
+        format!("{} {taken:>11}", star.format("%Y-%m-%d %H:%M:%S"))
+    }
+}
+
+impl<Tz: TimeZone> Display for DayTable<'_, Tz>
+where
+    Tz::Offset: Display,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let day = self.day;
+        let unlocked = unlock(self.year, day).with_timezone(&self.tz);
+        writeln!(
+            f,
+            "Day {day}, unlocked {}",
+            unlocked.format("%Y-%m-%d %H:%M %:z")
+        )?;
+
+        let mut members = self
+            .leaderboard
+            .members
+            .values()
+            .filter(|m| m.star(day, 1).is_some())
+            .collect::<Vec<_>>();
+        // Finished first, then by how far they got.
+        members.sort_by_key(|m| (m.star(day, 2).is_none(), m.star(day, 2), m.star(day, 1)));
+        let width = members
+            .iter()
+            .map(|m| m.name().len())
+            .max()
+            .unwrap_or(0)
+            .max(4);
+        writeln!(
+            f,
+            "{:<width$}  {:<31}  {:<31}  {:>11}",
+            "name", "part 1", "part 2", "delta"
+        )?;
+        for member in members {
+            let delta = member.delta(day).map_or_else(|| "-".to_string(), hms);
+            writeln!(
+                f,
+                "{:<width$}  {}  {}  {delta:>11}",
+                member.name(),
+                self.cell(member, 1),
+                self.cell(member, 2),
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Where `profile` keeps its copy of leaderboard `id`.
+pub fn path(profile: &Profile, year: usize, id: u64) -> PathBuf {
+    profile
+        .input_dir
+        .join(year.to_string())
+        .join(format!("leaderboard-{id}.json"))
+}
+
+/// Whether to download a copy that's `age` old, or missing. A copy younger than [`REFRESH`] is kept
+/// even when a refetch is asked for.
+fn refresh(fetch: Fetch, age: Option<Duration>) -> Fetch {
+    match (fetch, age) {
+        (Fetch::Never, _) => Fetch::Never,
+        (_, Some(age)) if age < REFRESH => Fetch::Never,
+        (_, Some(_)) => Fetch::Always,
+        (fetch, None) => fetch,
+    }
+}
+
+/// Reads private leaderboard `id`, downloading it with `profile`'s session when the copy is missing
+/// or old.
+pub fn load(profile: &Profile, year: usize, id: u64, fetch: Fetch) -> Result<Leaderboard> {
+    let path = path(profile, year, id);
+    let age = std::fs::metadata(&path)
+        .and_then(|meta| meta.modified())
+        .ok()
+        .map(|modified| modified.elapsed().unwrap_or_default());
+    let fetch = refresh(fetch, age);
+    let json = load_or_fetch(&path, fetch, &profile.fetcher()?, |f| {
+        f.leaderboard(year, id)
+    })?;
+    Leaderboard::parse(&json).wrap_err_with(|| format!("in {}", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::FixedOffset;
+
+    use super::*;
+
+    const PRIVATE: &str = include_str!("../fixtures/leaderboard/2024-private.json");
+
+    #[test]
+    fn parses() {
+        let leaderboard = Leaderboard::parse(PRIVATE).unwrap();
+        assert_eq!(leaderboard.year().unwrap(), 2024);
+        assert_eq!(leaderboard.owner_id, 1001);
+        assert_eq!(leaderboard.members.len(), 4);
+        assert_eq!(leaderboard.members[&1003].name(), "(anonymous user #1003)");
+        assert_eq!(leaderboard.members[&1001].completion_day_level[&4].len(), 1);
+        assert!(Leaderboard::parse("{\"event\": \"2024\"}").is_err());
+    }
+
+    #[test]
+    fn rankings() {
+        let leaderboard = Leaderboard::parse(PRIVATE).unwrap();
+        let names = leaderboard
+            .rankings()
+            .iter()
+            .map(|m| m.name())
+            .collect::<Vec<_>>();
+        assert_eq!(names, ["favilo", "Ada", "(anonymous user #1003)", "lurker"]);
+    }
+
+    #[test]
+    fn times() {
+        let leaderboard = Leaderboard::parse(PRIVATE).unwrap();
+        assert_eq!(unlock(2024, 1).timestamp(), 1733029200);
+        let favilo = &leaderboard.members[&1001];
+        assert_eq!(
+            favilo.solve_time(2024, 1, 1),
+            Some(Duration::from_secs(312))
+        );
+        assert_eq!(favilo.delta(1), Some(Duration::from_secs(235)));
+        assert_eq!(favilo.delta(3), Some(Duration::from_secs(89400)));
+        assert_eq!(favilo.delta(4), None);
+        assert_eq!(favilo.star(5, 1), None);
+        assert_eq!(hms(Duration::from_secs(89400)), "1d 00:50:00");
+        assert_eq!(hms(Duration::from_secs(235)), "0:03:55");
+    }
+
+    #[test]
+    fn tables() {
+        let leaderboard = Leaderboard::parse(PRIVATE).unwrap();
+        assert_eq!(
+            leaderboard.standings().to_string(),
+            "\
+rank  score  stars  days                       name
+   1     17      7  ***.                       favilo
+   2     12      4  **                         Ada
+   3      1      1  .                          (anonymous user #1003)
+   4      0      0                             lurker
+"
+        );
+
+        let new_york = FixedOffset::west_opt(5 * 3600).unwrap();
+        assert_eq!(
+            leaderboard.day(1, new_york).unwrap().to_string(),
+            "\
+Day 1, unlocked 2024-12-01 00:00 -05:00
+name                    part 1                           part 2                                 delta
+Ada                     2024-12-01 00:03:20     0:03:20  2024-12-01 00:04:20     0:04:20      0:01:00
+favilo                  2024-12-01 00:05:12     0:05:12  2024-12-01 00:09:07     0:09:07      0:03:55
+(anonymous user #1003)  2024-12-01 01:23:20     1:23:20  -                                          -
+"
+        );
+        assert!(leaderboard.day(0, new_york).is_err());
+        assert!(leaderboard.day(26, new_york).is_err());
+    }
+
+    #[test]
+    fn refreshes_at_most_every_15_minutes() {
+        let fresh = Some(Duration::from_secs(60));
+        let stale = Some(Duration::from_secs(3600));
+        assert_eq!(refresh(Fetch::Missing, None), Fetch::Missing);
+        assert_eq!(refresh(Fetch::Missing, fresh), Fetch::Never);
+        assert_eq!(refresh(Fetch::Always, fresh), Fetch::Never);
+        assert_eq!(refresh(Fetch::Missing, stale), Fetch::Always);
+        assert_eq!(refresh(Fetch::Never, stale), Fetch::Never);
+    }
+}
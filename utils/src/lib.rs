@@ -1,37 +1,70 @@
 #![feature(allocator_api)]
 #![feature(impl_trait_in_assoc_type)]
 #![feature(debug_closure_helpers)]
+#![feature(min_specialization)]
 #![warn(clippy::all)]
 //#![warn(clippy::pedantic)]
+pub mod answer;
+pub mod automaton;
+pub mod baseline;
+#[cfg(feature = "bench")]
+pub mod bench;
 pub mod collections;
-pub mod graph;
+pub mod config;
 pub mod errors;
+pub mod graph;
+pub mod leaderboard;
 pub mod macros;
 pub mod math;
+pub mod ocr;
 pub mod parse;
+pub mod progress;
+pub mod puzzle;
+pub mod runner;
+pub mod sim;
 pub mod traits;
 pub mod utils;
+pub mod viz;
 
-use std::fmt::Debug;
-use std::fs::read_to_string;
-use std::time::{Duration, Instant};
-
-use heapless::binary_heap::Max;
-use heapless::BinaryHeap;
-use miette::{Context, IntoDiagnostic, Result};
-use tracking_allocator::AllocationRegistry;
+use std::time::Duration;
 
-use self::utils::file::{download_input, get_input_path};
+use miette::Result;
 
-pub type Heap = BinaryHeap<StageTime, Max, 125>;
+use self::answer::ToAnswer;
+use self::config::Profile;
+use self::puzzle::Puzzle;
+use self::runner::RunOptions;
+use self::utils::file::{load_or_fetch, Fetch, Fetcher};
 
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub enum Stage {
     GetInput,
     Part1,
     Part2,
 }
 
+impl Stage {
+    /// The name used for benchmarks and baselines.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::GetInput => "get_input",
+            Self::Part1 => "part1",
+            Self::Part2 => "part2",
+        }
+    }
+}
+
+impl std::str::FromStr for Stage {
+    type Err = errors::Error;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        [Self::GetInput, Self::Part1, Self::Part2]
+            .into_iter()
+            .find(|stage| stage.name() == s)
+            .ok_or_else(|| errors::Error::InvalidInput(format!("unknown stage `{s}`")))
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct StageTime {
     pub time: Duration,
@@ -55,8 +88,8 @@ impl Ord for StageTime {
 impl StageTime {
     pub fn new<Part1, Part2, D: Runner<Part1, Part2>>(time: Duration, stage: Stage) -> Self
     where
-        Part1: Debug,
-        Part2: Debug,
+        Part1: ToAnswer,
+        Part2: ToAnswer,
     {
         Self {
             time,
@@ -66,6 +99,14 @@ impl StageTime {
         }
     }
 
+    pub fn day(&self) -> usize {
+        self.day
+    }
+
+    pub fn stage(&self) -> Stage {
+        self.stage
+    }
+
     pub fn log(&self, level: log::Level) {
         let comment = if self.comment.is_empty() {
             String::new()
@@ -85,8 +126,8 @@ impl StageTime {
 
 pub trait Runner<Part1 = usize, Part2 = usize>
 where
-    Part1: Debug,
-    Part2: Debug,
+    Part1: ToAnswer,
+    Part2: ToAnswer,
 {
     type Input<'input>;
 
@@ -96,69 +137,61 @@ where
         ""
     }
 
+    /// How long each stage may take before the runner moves on, instead of `--timeout`.
+    #[must_use]
+    fn timeout() -> Option<Duration> {
+        None
+    }
+
     fn get_input(_: &str) -> Result<Self::Input<'_>>;
     fn part1(_: &Self::Input<'_>) -> Result<Part1>;
     fn part2(_: &Self::Input<'_>) -> Result<Part2>;
+
+    /// Plays the puzzle in the terminal, usually through [`viz::play`]. Run with `--viz`.
+    fn visualize(_: &Self::Input<'_>) -> Result<()> {
+        miette::bail!("Day {} has no visualization", Self::day())
+    }
+}
+
+/// Something to do with every day of a year, in the order `run_days!` lists them. The macro
+/// generates a `for_each_day` function that calls [`DayVisitor::visit`] once per day.
+pub trait DayVisitor {
+    fn visit<R, Part1, Part2>(&mut self, year: usize)
+    where
+        R: Runner<Part1, Part2>,
+        Part1: ToAnswer,
+        Part2: ToAnswer;
+}
+
+/// Reads `profile`'s input for `day`, downloading it first if it isn't cached yet or `fetch` says
+/// to.
+pub fn load_input(profile: &Profile, year: usize, day: usize, fetch: Fetch) -> Result<String> {
+    let path = profile.input_path(year, day);
+    load_or_fetch(&path, fetch, &profile.fetcher()?, |f| f.input(year, day))
+}
+
+/// Reads the puzzle page for `day`, downloading it if it isn't cached. A cached page without part 2
+/// is downloaded again when there's a session, since part 2 may have been unlocked since.
+pub fn load_puzzle(profile: &Profile, year: usize, day: usize, fetch: Fetch) -> Result<Puzzle> {
+    let path = profile.puzzle_path(year, day);
+    let fetcher = profile.fetcher()?;
+    let download = |f: &Fetcher| f.puzzle(year, day);
+    let mut puzzle = Puzzle::parse(&load_or_fetch(&path, fetch, &fetcher, download)?);
+    if puzzle.parts.len() < 2 && fetch == Fetch::Missing && fetcher.has_session() {
+        puzzle = Puzzle::parse(&load_or_fetch(&path, Fetch::Always, &fetcher, download)?);
+    }
+    Ok(puzzle)
 }
 
-pub fn run<R, Part1, Part2>(
-    year: usize,
-    track: bool,
-    heap: &mut BinaryHeap<StageTime, Max, 125>,
-) -> Result<Duration>
+/// Plays `R`'s visualization on the input that `options` picks, fetched as it says.
+pub fn visualize<R, Part1, Part2>(year: usize, options: &RunOptions) -> Result<()>
 where
     R: Runner<Part1, Part2>,
-    Part1: Debug,
-    Part2: Debug,
+    Part1: ToAnswer,
+    Part2: ToAnswer,
 {
-    let comment = R::comment();
-    let comment = if comment.is_empty() {
-        String::new()
-    } else {
-        format!(" : {comment}")
-    };
-    log::info!("Day {}{}\n", R::day(), comment);
-    let input_full_path = get_input_path(year, R::day())?;
-    if !input_full_path.exists() {
-        let session = std::env::var("AOCSESSION")
-            .into_diagnostic()
-            .wrap_err("looking for AOCSESSION env var")?;
-        download_input(R::day(), year, &session, &input_full_path)?;
-    }
-    let input = read_to_string(input_full_path).map_err(|e| miette::miette!("{e}"))?;
-    let now = Instant::now();
-    if track {
-        AllocationRegistry::enable_tracking();
-    }
+    let profile = config::profile(options.profile.as_deref())?;
+    let input = load_input(&profile, year, R::day(), options.fetch)?;
     let input = R::get_input(&input)?;
-    let elapsed_i = now.elapsed();
-    heap.push(StageTime::new::<Part1, Part2, R>(
-        elapsed_i,
-        Stage::GetInput,
-    ))
-    .map_err(|e| miette::miette!("Too many stages: {e:?}"))?;
-    log::info!("Generation took {:?}", elapsed_i);
-
-    let now = Instant::now();
-    let output1 = R::part1(&input);
-    let elapsed1 = now.elapsed();
-    let output1 = output1?;
-    log::info!("Part 1 - {:?}", output1);
-    heap.push(StageTime::new::<Part1, Part2, R>(elapsed1, Stage::Part1))
-        .map_err(|e| miette::miette!("Too many stages: {e:?}"))?;
-    log::info!("Took {:?}", elapsed1);
-
-    let now = Instant::now();
-    let output2 = R::part2(&input);
-    let elapsed2 = now.elapsed();
-    let output2 = output2?;
-    if track {
-        AllocationRegistry::disable_tracking();
-    }
-
-    log::info!("Part 2 - {:?}", output2);
-    heap.push(StageTime::new::<Part1, Part2, R>(elapsed2, Stage::Part2))
-        .map_err(|e| miette::miette!("Too many stages: {e:?}"))?;
-    log::info!("Took {:?}\n", elapsed2);
-    Ok(elapsed_i + elapsed1 + elapsed2)
+    R::visualize(&input)
 }
@@ -2,34 +2,52 @@
 #[allow(clippy::crate_in_macro_def)]
 macro_rules! run_days {
     ($day:ident = $id:expr, $($days:ident = $ids:expr),* $(,)?) => {
-        use aoc_utils::{run, Heap, StageTime};
-        use heapless::{binary_heap::Max, BinaryHeap};
+        use aoc_utils::{
+            runner::{self, DayFn, DayReport, RunOptions},
+            visualize,
+        };
 
         pub mod $day;
         $(pub mod $days;)*
-        pub fn run_all(days: Vec<usize>, track: bool) -> miette::Result<Heap> {
-            let mut heap = BinaryHeap::<StageTime, Max, 125>::new();
+
+        const DAYS: &[(usize, DayFn)] = &[
+            ($id, runner::run_day::<$day::Day, _, _>),
+            $(($ids, runner::run_day::<$days::Day, _, _>),)*
+        ];
+
+        pub fn run_all(days: Vec<usize>, options: &RunOptions) -> miette::Result<Vec<DayReport>> {
+            Ok(runner::run_selected(crate::YEAR, DAYS, &days, options))
+        }
+
+        pub fn for_each_day<V: aoc_utils::DayVisitor>(visitor: &mut V) {
+            visitor.visit::<$day::Day, _, _>(crate::YEAR);
+            $(visitor.visit::<$days::Day, _, _>(crate::YEAR);)*
+        }
+
+        pub fn visualize_days(days: Vec<usize>, options: &RunOptions) -> miette::Result<()> {
             if days.is_empty() {
-                run::<$day::Day, _, _>(crate::YEAR, track, &mut heap)?;
-                $(run::<$days::Day, _, _>(crate::YEAR, track, &mut heap)?;)*
-            } else {
-                for day in days {
-                    match day {
-                        $id => run::<$day::Day, _, _>(crate::YEAR, track, &mut heap)?,
-                        $($ids => run::<$days::Day, _, _>(crate::YEAR, track, &mut heap)?,)*
-                        _ => panic!("Invalid day passed"),
-                    };
-                }
+                miette::bail!("Pick the days to visualize with `-d`");
             }
-
-            Ok(heap)
+            for day in days {
+                match day {
+                    $id => visualize::<$day::Day, _, _>(crate::YEAR, options)?,
+                    $($ids => visualize::<$days::Day, _, _>(crate::YEAR, options)?,)*
+                    _ => miette::bail!("Day {day} isn't in {}", crate::YEAR),
+                };
+            }
+            Ok(())
         }
     };
     () => {
-        use aoc_utils::{run, Heap, StageTime};
-        use heapless::{binary_heap::Max, BinaryHeap};
+        use aoc_utils::runner::{DayReport, RunOptions};
 
-        pub fn run_all(_days: Vec<usize>, _track: bool) -> miette::Result<Heap> {
+        pub fn run_all(_days: Vec<usize>, _options: &RunOptions) -> miette::Result<Vec<DayReport>> {
+            miette::bail!("No days specified")
+        }
+
+        pub fn for_each_day<V: aoc_utils::DayVisitor>(_visitor: &mut V) {}
+
+        pub fn visualize_days(_days: Vec<usize>, _options: &RunOptions) -> miette::Result<()> {
             miette::bail!("No days specified")
         }
     };
@@ -99,16 +117,37 @@ macro_rules! sample_case {
         };
     }
 
+/// Tests the answers to the real input. Answers for other profiles in `aoc.toml` can follow the
+/// default profile's, and every profile is checked against its own input:
+///
+/// ```ignore
+/// prod_case! {
+///     part1 = 1970720;
+///     part2 = 17191599;
+///     "alice" => part1 = 2264607; part2 = 19457120;
+/// }
+/// ```
 #[macro_export]
 #[allow(clippy::crate_in_macro_def)]
 macro_rules! prod_case {
-    (preamble = $preamble:expr; part1 = $part1:expr; part2 = $part2:expr;) => {
+    (
+        preamble = $preamble:expr;
+        part1 = $part1:expr;
+        part2 = $part2:expr;
+        $($profile:literal => part1 = $profile1:expr; part2 = $profile2:expr;)*
+    ) => {
         mod prod {
             use super::*;
-            use aoc_utils::utils::file::get_input_path;
+            use aoc_utils::config::{Config, Profile};
             use miette::{IntoDiagnostic, WrapErr};
             use std::fs::read_to_string;
 
+            fn read_input(profile: &Profile) -> miette::Result<String> {
+                read_to_string(profile.input_path(crate::YEAR, Day::day()))
+                    .into_diagnostic()
+                    .wrap_err_with(|| format!("failed to read {}'s input", profile.name()))
+            }
+
             #[test]
             fn part1() -> miette::Result<()> {
                 let _ = env_logger::try_init();
@@ -116,12 +155,13 @@ macro_rules! prod_case {
                 {
                     $preamble;
                 };
-                let input_path = get_input_path(crate::YEAR, Day::day())?;
-                let input = read_to_string(input_path)
-                    .into_diagnostic()
-                    .wrap_err("failed to read input")?;
-                let input = Day::get_input(&input)?;
-                assert_eq!($part1, Day::part1(&input)?);
+                let config = Config::load()?;
+                for (profile, expected) in [(None::<&str>, $part1) $(, (Some($profile), $profile1))*] {
+                    let profile = config.profile(profile)?;
+                    let input = read_input(&profile)?;
+                    let input = Day::get_input(&input)?;
+                    assert_eq!(expected, Day::part1(&input)?, "profile {}", profile.name());
+                }
                 Ok(())
             }
 
@@ -132,17 +172,27 @@ macro_rules! prod_case {
                 {
                     $preamble;
                 };
-                let input_path = get_input_path(crate::YEAR, Day::day())?;
-                let input = read_to_string(input_path)
-                    .into_diagnostic()
-                    .wrap_err("failed to read input")?;
-                let input = Day::get_input(&input)?;
-                assert_eq!($part2, Day::part2(&input)?);
+                let config = Config::load()?;
+                for (profile, expected) in [(None::<&str>, $part2) $(, (Some($profile), $profile2))*] {
+                    let profile = config.profile(profile)?;
+                    let input = read_input(&profile)?;
+                    let input = Day::get_input(&input)?;
+                    assert_eq!(expected, Day::part2(&input)?, "profile {}", profile.name());
+                }
                 Ok(())
             }
         }
     };
-    (part1 = $part1:expr; part2 = $part2:expr;) => {
-        prod_case! { preamble = (); part1 = $part1; part2 = $part2; }
+    (
+        part1 = $part1:expr;
+        part2 = $part2:expr;
+        $($profile:literal => part1 = $profile1:expr; part2 = $profile2:expr;)*
+    ) => {
+        prod_case! {
+            preamble = ();
+            part1 = $part1;
+            part2 = $part2;
+            $($profile => part1 = $profile1; part2 = $profile2;)*
+        }
     };
 }
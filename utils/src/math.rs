@@ -1,4 +1,10 @@
 pub mod coord;
+pub mod linear;
+pub mod number;
+
+pub use number::{
+    checked_lcm_all, crt, extended_gcd, gcd_all, lcm_all, mod_inverse, mod_pow, mul_mod,
+};
 
 #[must_use]
 pub fn mean(l: &[usize]) -> f64 {
@@ -1,6 +1,6 @@
 use std::ops::{Add, AddAssign, Mul, Neg, Sub};
 
-use crate::collections::bitset::{Dim, Dimension, FromBitSetIndex, ToBitSetIndex};
+use crate::collections::bitset::{Dim, Dimension, Error, FromBitSetIndex, ToBitSetIndex};
 
 #[derive(Default, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub struct Coord(pub isize, pub isize);
@@ -143,19 +143,130 @@ impl From<&Coord> for (usize, usize) {
     }
 }
 
+/// Maps a signed value onto the naturals: `0, -1, 1, -2, 2, ...` become `0, 1, 2, 3, 4, ...`.
+fn zigzag(n: isize) -> usize {
+    ((n << 1) ^ (n >> (isize::BITS - 1))) as usize
+}
+
+fn unzigzag(n: usize) -> isize {
+    (n >> 1) as isize ^ -((n & 1) as isize)
+}
+
+/// Szudzik's pairing function, which keeps a square of side `n` around the origin inside the
+/// first `n * n` indices. Indices grow with the square of the larger coordinate, so on 64-bit
+/// targets this gives up, with `None`, once a coordinate is about 2³¹ away from the origin.
+fn pair(a: usize, b: usize) -> Option<usize> {
+    if a < b {
+        b.checked_mul(b)?.checked_add(a)
+    } else {
+        a.checked_mul(a)?.checked_add(a)?.checked_add(b)
+    }
+}
+
+fn unpair(z: usize) -> (usize, usize) {
+    let s = z.isqrt();
+    let rest = z - s * s;
+    if rest < s {
+        (rest, s)
+    } else {
+        (s, rest - s)
+    }
+}
+
+impl Coord {
+    fn unbounded_index(&self) -> Result<usize, Error> {
+        pair(zigzag(self.0), zigzag(self.1))
+            .ok_or_else(|| Error::OutOfBounds(Box::new([self.0, self.1]), Dim::Unbounded))
+    }
+}
+
+/// Without bounds, both coordinates have to be within about 2³¹ of the origin.
 impl ToBitSetIndex for Coord {
     fn to_bitset_index(&self, dim: &Dim) -> usize {
-        let dim = dim.bounds().expect("invalid bounds");
-        self.0 as usize + self.1 as usize * dim[0]
+        let Some(bounds) = dim.bounds() else {
+            return self.unbounded_index().unwrap_or_else(|e| panic!("{e}"));
+        };
+        let origin = dim.origin().unwrap_or(&[0, 0]);
+        debug_assert!(
+            dim.normalize([self.0, self.1]).is_some(),
+            "{self:?} is out of bounds for {dim:?}"
+        );
+        (self.0 - origin[0]) as usize + (self.1 - origin[1]) as usize * bounds[0]
+    }
+
+    fn try_to_bitset_index(&self, dim: &Dim) -> Result<usize, Error> {
+        let Some(bounds) = dim.bounds() else {
+            return self.unbounded_index();
+        };
+        let [x, y] = dim
+            .normalize([self.0, self.1])
+            .ok_or_else(|| Error::OutOfBounds(Box::new([self.0, self.1]), dim.clone()))?;
+        Ok(x + y * bounds[0])
     }
 }
 
 impl FromBitSetIndex for Coord {
     fn from_bitset_index(index: usize, dim: &Dim) -> Self {
-        let dim = dim.bounds().expect("invalid bounds");
+        let Some(bounds) = dim.bounds() else {
+            let (x, y) = unpair(index);
+            return Self(unzigzag(x), unzigzag(y));
+        };
+        let origin = dim.origin().unwrap_or(&[0, 0]);
         Self(
-            (index % dim[0]).try_into().unwrap(),
-            (index / dim[0]).try_into().unwrap(),
+            isize::try_from(index % bounds[0]).unwrap() + origin[0],
+            isize::try_from(index / bounds[0]).unwrap() + origin[1],
         )
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::collections::bitset::BitSet;
+
+    #[test]
+    fn unbounded_bitset() {
+        let coords = [Coord(0, 0), Coord(-3, 7), Coord(1000, -1000), Coord(-1, -1)];
+        let set: BitSet<Coord> = coords.iter().copied().collect();
+        assert_eq!(set.len(), coords.len());
+        for c in coords {
+            assert!(set.contains(&c));
+            assert_eq!(
+                Coord::from_bitset_index(c.to_bitset_index(&Dim::Unbounded), &Dim::Unbounded),
+                c
+            );
+        }
+        assert!(!set.contains(&Coord(3, -7)));
+
+        for far in [Coord(-(1 << 31), 5), Coord(5, (1 << 31) - 1)] {
+            let index = far.to_bitset_index(&Dim::Unbounded);
+            assert_eq!(Coord::from_bitset_index(index, &Dim::Unbounded), far);
+        }
+        let too_far = Coord(1 << 31, 0);
+        assert_eq!(
+            too_far.try_to_bitset_index(&Dim::Unbounded),
+            Err(Error::OutOfBounds(Box::new([1 << 31, 0]), Dim::Unbounded))
+        );
+        assert!(!set.contains(&too_far));
+    }
+
+    #[test]
+    fn offset_bitset() {
+        let mut set: BitSet<Coord> = BitSet::with_bounds(([-2, -2], [5, 5]));
+        assert_eq!(set.try_insert(Coord(-2, 2)), Ok(true));
+        assert_eq!(set.try_insert(Coord(2, -2)), Ok(true));
+        assert!(set.try_insert(Coord(3, 0)).is_err());
+        assert!(set.try_insert(Coord(0, -3)).is_err());
+        assert_eq!(
+            set.iter().collect::<Vec<_>>(),
+            vec![Coord(2, -2), Coord(-2, 2)]
+        );
+    }
+
+    #[test]
+    fn fixed_bitset_rejects_negative() {
+        let mut set: BitSet<Coord> = BitSet::with_bounds([4, 4]);
+        assert!(set.try_insert(Coord(-1, 1)).is_err());
+        assert!(!set.contains(&Coord(-1, 1)));
+    }
+}
@@ -0,0 +1,225 @@
+//! Exact solvers for the small integer linear systems that puzzles like to hide behind button
+//! presses, without going through floats and rounding.
+use std::ops::RangeInclusive;
+
+use num::{rational::Ratio, Integer, Zero};
+
+use super::number::extended_gcd;
+
+pub type Rational = Ratio<i128>;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Solution {
+    /// The equations contradict each other.
+    Inconsistent,
+    Unique(Vec<Rational>),
+    /// Infinitely many solutions: `particular` is one of them, with every variable in `free`
+    /// set to zero, and those variables can take any value.
+    Underdetermined {
+        particular: Vec<Rational>,
+        free: Vec<usize>,
+    },
+}
+
+impl Solution {
+    /// The unique solution, if it exists and is all integers.
+    pub fn integer(&self) -> Option<Vec<i128>> {
+        match self {
+            Self::Unique(xs) => xs
+                .iter()
+                .map(|x| x.is_integer().then(|| x.to_integer()))
+                .collect(),
+            _ => None,
+        }
+    }
+
+    /// The unique solution, if it exists and is all nonnegative integers.
+    pub fn nonnegative_integer(&self) -> Option<Vec<i128>> {
+        self.integer().filter(|xs| xs.iter().all(|&x| x >= 0))
+    }
+}
+
+/// Solves `a * x = b` by Gaussian elimination over the rationals.
+///
+/// `a` is given row by row, one row per equation. Panics if the rows don't all have the same
+/// length or if `b` doesn't have one entry per row.
+pub fn solve<R: AsRef<[i128]>>(a: &[R], b: &[i128]) -> Solution {
+    assert_eq!(a.len(), b.len(), "one right hand side per equation");
+    let vars = a.first().map_or(0, |r| r.as_ref().len());
+    let mut rows = a
+        .iter()
+        .zip(b)
+        .map(|(row, &rhs)| {
+            let row = row.as_ref();
+            assert_eq!(row.len(), vars, "every equation needs the same variables");
+            row.iter()
+                .chain([&rhs])
+                .map(|&v| Rational::from_integer(v))
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+
+    let mut pivots = Vec::with_capacity(vars);
+    for col in 0..vars {
+        let rank = pivots.len();
+        let Some(pivot) = (rank..rows.len()).find(|&r| !rows[r][col].is_zero()) else {
+            continue;
+        };
+        rows.swap(rank, pivot);
+        let lead = rows[rank][col];
+        rows[rank].iter_mut().for_each(|v| *v /= lead);
+        let pivot_row = rows[rank].clone();
+        for (r, row) in rows.iter_mut().enumerate() {
+            let factor = row[col];
+            if r != rank && !factor.is_zero() {
+                row.iter_mut()
+                    .zip(&pivot_row)
+                    .for_each(|(v, p)| *v -= factor * p);
+            }
+        }
+        pivots.push(col);
+    }
+
+    if rows[pivots.len()..].iter().any(|row| !row[vars].is_zero()) {
+        return Solution::Inconsistent;
+    }
+    let mut particular = vec![Rational::zero(); vars];
+    for (row, &col) in pivots.iter().enumerate() {
+        particular[col] = rows[row][vars];
+    }
+    if pivots.len() == vars {
+        Solution::Unique(particular)
+    } else {
+        let free = (0..vars).filter(|c| !pivots.contains(c)).collect();
+        Solution::Underdetermined { particular, free }
+    }
+}
+
+/// Solves a 2x2 system with Cramer's rule, without any rationals.
+///
+/// Returns `None` for a singular matrix; use [`solve`] to tell apart the inconsistent and
+/// underdetermined cases.
+pub fn solve2(a: [[i128; 2]; 2], b: [i128; 2]) -> Option<[Rational; 2]> {
+    let det = a[0][0] * a[1][1] - a[0][1] * a[1][0];
+    (det != 0).then(|| {
+        [
+            Rational::new(b[0] * a[1][1] - a[0][1] * b[1], det),
+            Rational::new(a[0][0] * b[1] - b[0] * a[1][0], det),
+        ]
+    })
+}
+
+/// Every integer solution of `a * x + b * y = c` with positive `a` and `b`, as
+/// `(x0 + k * dx, y0 - k * dy)` for integer `k`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Diophantine {
+    x0: i128,
+    y0: i128,
+    dx: i128,
+    dy: i128,
+}
+
+impl Diophantine {
+    /// `None` if there are no integer solutions. Panics unless `a` and `b` are positive.
+    pub fn new(a: i128, b: i128, c: i128) -> Option<Self> {
+        assert!(a > 0 && b > 0, "coefficients must be positive");
+        let (g, x, y) = extended_gcd(a, b);
+        if c % g != 0 {
+            return None;
+        }
+        let scale = c / g;
+        Some(Self {
+            x0: x.checked_mul(scale)?,
+            y0: y.checked_mul(scale)?,
+            dx: b / g,
+            dy: a / g,
+        })
+    }
+
+    pub fn at(&self, k: i128) -> (i128, i128) {
+        (self.x0 + k * self.dx, self.y0 - k * self.dy)
+    }
+
+    /// The values of `k` for which both `x` and `y` are nonnegative.
+    pub fn nonnegative(&self) -> Option<RangeInclusive<i128>> {
+        let low = Integer::div_ceil(&-self.x0, &self.dx);
+        let high = Integer::div_floor(&self.y0, &self.dy);
+        (low <= high).then_some(low..=high)
+    }
+
+    /// The nonnegative solution with the smallest `cost_x * x + cost_y * y`.
+    pub fn min_cost(&self, cost_x: i128, cost_y: i128) -> Option<(i128, i128)> {
+        let range = self.nonnegative()?;
+        // The cost is linear in `k`, so one of the two ends is the cheapest.
+        [*range.start(), *range.end()]
+            .map(|k| self.at(k))
+            .into_iter()
+            .min_by_key(|(x, y)| cost_x * x + cost_y * y)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ints(xs: &[i128]) -> Vec<Rational> {
+        xs.iter().copied().map(Rational::from_integer).collect()
+    }
+
+    #[test]
+    fn unique() {
+        let solution = solve(&[[94, 22], [34, 67]], &[8400, 5400]);
+        assert_eq!(solution, Solution::Unique(ints(&[80, 40])));
+        assert_eq!(solution.nonnegative_integer(), Some(vec![80, 40]));
+
+        let solution = solve(&[[26, 67], [66, 21]], &[12748, 12176]);
+        assert!(matches!(solution, Solution::Unique(_)));
+        assert_eq!(solution.integer(), None);
+
+        let solution = solve(&[[1, 1], [1, -1]], &[0, 4]);
+        assert_eq!(solution.integer(), Some(vec![2, -2]));
+        assert_eq!(solution.nonnegative_integer(), None);
+    }
+
+    #[test]
+    fn degenerate() {
+        assert_eq!(solve(&[[1, 2], [2, 4]], &[3, 7]), Solution::Inconsistent);
+        assert_eq!(
+            solve(&[[1, 2], [2, 4]], &[3, 6]),
+            Solution::Underdetermined {
+                particular: ints(&[3, 0]),
+                free: vec![1],
+            }
+        );
+        assert_eq!(
+            solve(&[[1, 0, 0], [0, 1, 0], [0, 0, 1], [1, 1, 1]], &[1, 2, 3, 6]),
+            Solution::Unique(ints(&[1, 2, 3]))
+        );
+    }
+
+    #[test]
+    fn cramer() {
+        let offset = 10_000_000_000_000;
+        let [a, b] = solve2([[94, 22], [34, 67]], [8400, 5400]).unwrap();
+        assert_eq!((a, b), (80.into(), 40.into()));
+        let [a, b] = solve2([[26, 67], [66, 21]], [12748 + offset, 12176 + offset]).unwrap();
+        assert_eq!((a, b), (118679050709.into(), 103199174542.into()));
+        assert_eq!(solve2([[1, 2], [2, 4]], [3, 6]), None);
+    }
+
+    #[test]
+    fn diophantine() {
+        let d = Diophantine::new(3, 5, 22).unwrap();
+        let range = d.nonnegative().unwrap();
+        let all = range.map(|k| d.at(k)).collect::<Vec<_>>();
+        assert_eq!(all, vec![(4, 2)]);
+
+        let d = Diophantine::new(2, 4, 20).unwrap();
+        assert_eq!(d.nonnegative().unwrap().count(), 6);
+        assert_eq!(d.min_cost(3, 1), Some((0, 5)));
+        assert_eq!(d.min_cost(1, 3), Some((10, 0)));
+
+        assert_eq!(Diophantine::new(2, 4, 7), None);
+        assert_eq!(Diophantine::new(5, 7, 3).unwrap().nonnegative(), None);
+    }
+}
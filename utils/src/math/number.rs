@@ -0,0 +1,226 @@
+//! Number theory helpers for the cycle alignment puzzles.
+//!
+//! Everything that multiplies goes through `i128`, so any integer type up to 64 bits, `usize` and
+//! `isize` included, can't overflow in the middle of a computation; results that don't fit back
+//! into `N` come out as `None`. Moduli have to be positive.
+use num::Integer;
+
+/// Integer types that convert to and from `i128`. `usize` and `isize` only have `TryFrom`, but
+/// always fit; a `u128` above `i128::MAX` panics.
+pub trait WideInt: Integer + Copy + TryInto<i128> + TryFrom<i128> {}
+
+impl<T> WideInt for T where T: Integer + Copy + TryInto<i128> + TryFrom<i128> {}
+
+fn widen<N: WideInt>(n: N) -> i128 {
+    n.try_into()
+        .unwrap_or_else(|_| panic!("value doesn't fit in an i128"))
+}
+
+/// Greatest common divisor of every number, `0` for an empty iterator.
+pub fn gcd_all<N, I>(iter: I) -> N
+where
+    N: Integer,
+    I: IntoIterator<Item = N>,
+{
+    iter.into_iter().fold(N::zero(), |acc, n| acc.gcd(&n))
+}
+
+/// Least common multiple of every number, `1` for an empty iterator.
+pub fn lcm_all<N, I>(iter: I) -> N
+where
+    N: Integer,
+    I: IntoIterator<Item = N>,
+{
+    iter.into_iter().fold(N::one(), |acc, n| acc.lcm(&n))
+}
+
+/// Like [`lcm_all`], but `None` if the result doesn't fit in `N`.
+pub fn checked_lcm_all<N, I>(iter: I) -> Option<N>
+where
+    N: WideInt,
+    I: IntoIterator<Item = N>,
+{
+    iter.into_iter()
+        .try_fold(1i128, |acc, n| {
+            let n = widen(n);
+            if n == 0 {
+                return Some(0);
+            }
+            (acc / acc.gcd(&n)).checked_mul(n).map(i128::abs)
+        })
+        .and_then(|l| N::try_from(l).ok())
+}
+
+/// `(g, x, y)` such that `a * x + b * y == g == gcd(a, b)`.
+pub fn extended_gcd(a: i128, b: i128) -> (i128, i128, i128) {
+    let (mut old_r, mut r) = (a, b);
+    let (mut old_x, mut x) = (1, 0);
+    let (mut old_y, mut y) = (0, 1);
+    while r != 0 {
+        let q = old_r / r;
+        (old_r, r) = (r, old_r - q * r);
+        (old_x, x) = (x, old_x - q * x);
+        (old_y, y) = (y, old_y - q * y);
+    }
+    if old_r < 0 {
+        (-old_r, -old_x, -old_y)
+    } else {
+        (old_r, old_x, old_y)
+    }
+}
+
+/// `a * b mod m`, in `0..m`, for any `m` that fits in an `i128`.
+pub fn mul_mod(a: i128, b: i128, m: i128) -> i128 {
+    assert!(m > 0, "modulus must be positive");
+    let (mut a, mut b) = (a.rem_euclid(m), b.rem_euclid(m));
+    if let Some(product) = a.checked_mul(b) {
+        return product % m;
+    }
+    // Double and add, keeping every intermediate below `m`.
+    let add = |x: i128, y: i128| if x >= m - y { x - (m - y) } else { x + y };
+    let mut result = 0;
+    while b > 0 {
+        if b & 1 == 1 {
+            result = add(result, a);
+        }
+        a = add(a, a);
+        b >>= 1;
+    }
+    result
+}
+
+/// `base ^ exp mod m`, in `0..m`. Panics on a negative exponent or a modulus that isn't positive.
+pub fn mod_pow<N: WideInt>(base: N, exp: N, m: N) -> N {
+    let m = widen(m);
+    let mut exp = widen(exp);
+    assert!(m > 0, "modulus must be positive");
+    assert!(exp >= 0, "negative exponent");
+    let mut base = widen(base).rem_euclid(m);
+    let mut result = 1 % m;
+    while exp > 0 {
+        if exp & 1 == 1 {
+            result = mul_mod(result, base, m);
+        }
+        base = mul_mod(base, base, m);
+        exp >>= 1;
+    }
+    narrow(result)
+}
+
+/// The `x` in `0..m` with `a * x == 1 mod m`, if `a` and `m` are coprime. `None` if `m` isn't
+/// positive.
+pub fn mod_inverse<N: WideInt>(a: N, m: N) -> Option<N> {
+    let m = widen(m);
+    if m <= 0 {
+        return None;
+    }
+    let (g, x, _) = extended_gcd(widen(a).rem_euclid(m), m);
+    (g == 1).then(|| narrow(x.rem_euclid(m)))
+}
+
+/// Chinese remainder theorem over `(residue, modulus)` pairs.
+///
+/// Returns `(x, l)` where `l` is the lcm of the moduli and `x` in `0..l` is the only solution
+/// modulo `l`. The moduli don't have to be coprime; `None` if the congruences contradict each
+/// other, if a modulus isn't positive, or if `l` doesn't fit in `N`.
+pub fn crt<N, I>(congruences: I) -> Option<(N, N)>
+where
+    N: WideInt,
+    I: IntoIterator<Item = (N, N)>,
+{
+    let (x, l) = congruences
+        .into_iter()
+        .try_fold((0i128, 1i128), |(r1, m1), (r2, m2)| {
+            let (r2, m2) = (widen(r2), widen(m2));
+            if m2 <= 0 {
+                return None;
+            }
+            let r2 = r2.rem_euclid(m2);
+            let (g, p, _) = extended_gcd(m1, m2);
+            let diff = r2 - r1;
+            if diff % g != 0 {
+                return None;
+            }
+            let step = m2 / g;
+            let l = (m1 / g).checked_mul(m2)?;
+            // m1 * k == diff (mod m2), with k = diff / g * p (mod m2 / g)
+            let k = mul_mod(diff / g, p, step);
+            Some(((r1 + mul_mod(m1, k, l)) % l, l))
+        })?;
+    Some((N::try_from(x).ok()?, N::try_from(l).ok()?))
+}
+
+/// Converts a result that is already known to be below the `N` modulus back into `N`.
+fn narrow<N: WideInt>(n: i128) -> N {
+    N::try_from(n).unwrap_or_else(|_| unreachable!("{n} is below a modulus that fit"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn folds() {
+        assert_eq!(gcd_all([12, 18, 30]), 6);
+        assert_eq!(gcd_all(Vec::<u32>::new()), 0);
+        assert_eq!(lcm_all([4u64, 6, 10]), 60);
+        assert_eq!(lcm_all(Vec::<u8>::new()), 1);
+        assert_eq!(checked_lcm_all([4u64, 6, 10]), Some(60));
+        assert_eq!(checked_lcm_all([u64::MAX, u64::MAX - 1]), None);
+        assert_eq!(checked_lcm_all([200u8, 3]), None);
+    }
+
+    #[test]
+    fn inverses() {
+        let (g, x, y) = extended_gcd(240, 46);
+        assert_eq!(g, 2);
+        assert_eq!(240 * x + 46 * y, 2);
+        assert_eq!(mod_inverse(3, 11), Some(4));
+        assert_eq!(mod_inverse(-3, 11), Some(7));
+        assert_eq!(mod_inverse(4, 10), None);
+    }
+
+    #[test]
+    fn powers() {
+        assert_eq!(mod_pow(4, 13, 497), 445);
+        assert_eq!(mod_pow(2u64, 0, 1), 0);
+        assert_eq!(mod_pow(-2i64, 3, 5), 2);
+        let m = u64::MAX - 58; // largest 64 bit prime
+        assert_eq!(mod_pow(3u64, m - 1, m), 1);
+        let big = i128::MAX;
+        assert_eq!(mul_mod(big - 1, big - 1, big), 1);
+    }
+
+    #[test]
+    fn chinese_remainder() {
+        // 2020 day 13 sample: bus `id` at offset `i` needs t == -i mod id.
+        let buses = [(0, 7), (1, 13), (4, 59), (6, 31), (7, 19)];
+        let (t, l) = crt(buses.map(|(i, id): (i64, i64)| (-i, id))).unwrap();
+        assert_eq!(t, 1068781);
+        assert_eq!(l, 7 * 13 * 59 * 31 * 19);
+
+        assert_eq!(crt([(2u32, 6), (4, 8)]), Some((20, 24)));
+        assert_eq!(crt([(1u32, 6), (2, 8)]), None);
+        assert_eq!(crt(Vec::<(u8, u8)>::new()), Some((0, 1)));
+        assert_eq!(crt([(0u8, 16), (1, 17)]), None);
+        assert_eq!(crt([(1i64, 0)]), None);
+        assert_eq!(crt([(1i64, -5)]), None);
+    }
+
+    #[test]
+    fn pointer_sized() {
+        assert_eq!(crt([(2usize, 3), (3, 5), (2, 7)]), Some((23, 105)));
+        assert_eq!(mod_pow(3usize, 200, 1_000_000_007), 136_318_165);
+        assert_eq!(mod_inverse(3usize, 11), Some(4));
+        assert_eq!(mod_inverse(-3isize, 11), Some(7));
+        assert_eq!(mod_inverse(3isize, 0), None);
+        assert_eq!(checked_lcm_all([4usize, 6, 10]), Some(60));
+        assert_eq!(checked_lcm_all([usize::MAX, usize::MAX - 1]), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "modulus must be positive")]
+    fn non_positive_modulus() {
+        mod_pow(2isize, 3, -7);
+    }
+}
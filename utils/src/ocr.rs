@@ -0,0 +1,246 @@
+//! Reads the capital letters some puzzles draw instead of printing an answer.
+//!
+//! Both of the fonts AoC uses are known: the small one is 6 pixels tall (2016 day 8, 2019 days 8
+//! and 11, 2021 day 13, 2022 day 10) and the large one is 10 (2018 day 10). Letters are split
+//! apart at empty columns, so the gap between them doesn't matter.
+use ndarray::Array2;
+
+use crate::answer::Answer;
+
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum Error {
+    #[error("no font is {0} pixels tall")]
+    UnknownHeight(usize),
+    #[error("letter {index} isn't in the font:\n{glyph}")]
+    UnknownGlyph { index: usize, glyph: String },
+    #[error("there's nothing lit")]
+    Empty,
+}
+
+/// Letters 6 pixels tall, usually 4 wide with a space between them.
+const SMALL: &[(char, &str)] = &[
+    ('A', ".##.\n#..#\n#..#\n####\n#..#\n#..#"),
+    ('B', "###.\n#..#\n###.\n#..#\n#..#\n###."),
+    ('C', ".##.\n#..#\n#...\n#...\n#..#\n.##."),
+    ('E', "####\n#...\n###.\n#...\n#...\n####"),
+    ('F', "####\n#...\n###.\n#...\n#...\n#..."),
+    ('G', ".##.\n#..#\n#...\n#.##\n#..#\n.###"),
+    ('H', "#..#\n#..#\n####\n#..#\n#..#\n#..#"),
+    ('I', "###\n.#.\n.#.\n.#.\n.#.\n###"),
+    ('J', "..##\n...#\n...#\n...#\n#..#\n.##."),
+    ('K', "#..#\n#.#.\n##..\n#.#.\n#.#.\n#..#"),
+    ('L', "#...\n#...\n#...\n#...\n#...\n####"),
+    ('O', ".##.\n#..#\n#..#\n#..#\n#..#\n.##."),
+    ('P', "###.\n#..#\n#..#\n###.\n#...\n#..."),
+    ('R', "###.\n#..#\n#..#\n###.\n#.#.\n#..#"),
+    ('S', ".###\n#...\n#...\n.##.\n...#\n###."),
+    ('U', "#..#\n#..#\n#..#\n#..#\n#..#\n.##."),
+    ('Y', "#...#\n#...#\n.#.#.\n..#..\n..#..\n..#.."),
+    ('Z', "####\n...#\n..#.\n.#..\n#...\n####"),
+];
+
+/// Letters 10 pixels tall and 6 wide.
+const LARGE: &[(char, &str)] = &[
+    (
+        'A',
+        "..##..\n.#..#.\n#....#\n#....#\n#....#\n######\n#....#\n#....#\n#....#\n#....#",
+    ),
+    (
+        'B',
+        "#####.\n#....#\n#....#\n#....#\n#####.\n#....#\n#....#\n#....#\n#....#\n#####.",
+    ),
+    (
+        'C',
+        ".####.\n#....#\n#.....\n#.....\n#.....\n#.....\n#.....\n#.....\n#....#\n.####.",
+    ),
+    (
+        'E',
+        "######\n#.....\n#.....\n#.....\n#####.\n#.....\n#.....\n#.....\n#.....\n######",
+    ),
+    (
+        'F',
+        "######\n#.....\n#.....\n#.....\n#####.\n#.....\n#.....\n#.....\n#.....\n#.....",
+    ),
+    (
+        'G',
+        ".####.\n#....#\n#.....\n#.....\n#.....\n#..###\n#....#\n#....#\n#...##\n.###.#",
+    ),
+    (
+        'H',
+        "#....#\n#....#\n#....#\n#....#\n######\n#....#\n#....#\n#....#\n#....#\n#....#",
+    ),
+    (
+        'J',
+        "...###\n....#.\n....#.\n....#.\n....#.\n....#.\n....#.\n#...#.\n#...#.\n.###..",
+    ),
+    (
+        'K',
+        "#....#\n#...#.\n#..#..\n#.#...\n##....\n##....\n#.#...\n#..#..\n#...#.\n#....#",
+    ),
+    (
+        'L',
+        "#.....\n#.....\n#.....\n#.....\n#.....\n#.....\n#.....\n#.....\n#.....\n######",
+    ),
+    (
+        'N',
+        "#....#\n##...#\n##...#\n#.#..#\n#.#..#\n#..#.#\n#..#.#\n#...##\n#...##\n#....#",
+    ),
+    (
+        'P',
+        "#####.\n#....#\n#....#\n#....#\n#####.\n#.....\n#.....\n#.....\n#.....\n#.....",
+    ),
+    (
+        'R',
+        "#####.\n#....#\n#....#\n#....#\n#####.\n#..#..\n#...#.\n#...#.\n#....#\n#....#",
+    ),
+    (
+        'X',
+        "#....#\n#....#\n.#..#.\n.#..#.\n..##..\n..##..\n.#..#.\n.#..#.\n#....#\n#....#",
+    ),
+    (
+        'Z',
+        "######\n.....#\n.....#\n....#.\n...#..\n..#...\n.#....\n#.....\n#.....\n######",
+    ),
+];
+
+/// Reads the letters out of rows of pixels. Rows can be ragged; missing pixels are dark.
+pub fn read_rows<I, R>(rows: I) -> Result<String, Error>
+where
+    I: IntoIterator<Item = R>,
+    R: IntoIterator<Item = bool>,
+{
+    let mut rows = rows
+        .into_iter()
+        .map(|row| row.into_iter().collect::<Vec<_>>())
+        .collect::<Vec<_>>();
+    // Blank rows above and below don't count towards the height.
+    let first = rows
+        .iter()
+        .position(|row| row.contains(&true))
+        .ok_or(Error::Empty)?;
+    let last = rows.iter().rposition(|row| row.contains(&true)).unwrap();
+    rows.truncate(last + 1);
+    rows.drain(..first);
+
+    let font = match rows.len() {
+        6 => SMALL,
+        10 => LARGE,
+        height => return Err(Error::UnknownHeight(height)),
+    };
+    let width = rows.iter().map(Vec::len).max().unwrap_or(0);
+    let lit = |x: usize| rows.iter().any(|row| row.get(x) == Some(&true));
+
+    let mut letters = String::new();
+    let mut x = 0;
+    while x < width {
+        if !lit(x) {
+            x += 1;
+            continue;
+        }
+        let start = x;
+        while x < width && lit(x) {
+            x += 1;
+        }
+        let glyph = rows
+            .iter()
+            .map(|row| {
+                (start..x)
+                    .map(|x| if row.get(x) == Some(&true) { '#' } else { '.' })
+                    .collect::<String>()
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+        let letter = font
+            .iter()
+            .find(|(_, g)| *g == glyph)
+            .map(|&(c, _)| c)
+            .ok_or(Error::UnknownGlyph {
+                index: letters.len(),
+                glyph,
+            })?;
+        letters.push(letter);
+    }
+    Ok(letters)
+}
+
+pub fn read(grid: &Array2<bool>) -> Result<String, Error> {
+    read_rows(grid.rows().into_iter().map(|row| row.to_vec()))
+}
+
+/// Reads art drawn with `#` for lit pixels; anything else is dark.
+pub fn read_str(art: &str) -> Result<String, Error> {
+    read_rows(art.lines().map(|line| line.chars().map(|c| c == '#')))
+}
+
+/// Reads the letters of an [`Answer::Art`]. Other answers are already text.
+pub fn read_answer(answer: &Answer) -> Result<Answer, Error> {
+    match answer {
+        Answer::Art(art) => read_str(art).map(Answer::Text),
+        answer => Ok(answer.clone()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn render(font: &[(char, &str)], word: &str, gap: usize) -> String {
+        let glyphs = word
+            .chars()
+            .map(|c| font.iter().find(|(g, _)| *g == c).unwrap().1)
+            .map(|g| g.lines().collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+        (0..glyphs[0].len())
+            .map(|y| {
+                glyphs
+                    .iter()
+                    .map(|g| g[y])
+                    .collect::<Vec<_>>()
+                    .join(&".".repeat(gap))
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    #[test]
+    fn every_letter() {
+        let small = SMALL.iter().map(|(c, _)| c).collect::<String>();
+        assert_eq!(read_str(&render(SMALL, &small, 1)), Ok(small));
+        let large = LARGE.iter().map(|(c, _)| c).collect::<String>();
+        assert_eq!(read_str(&render(LARGE, &large, 2)), Ok(large));
+    }
+
+    #[test]
+    fn crt_screen() {
+        // A 2022 day 10 screen, with an empty column at the end and a blank line underneath.
+        let screen = "\
+            ###..#....###...##..####.###...##..#....\n\
+            #..#.#....#..#.#..#.#....#..#.#..#.#....\n\
+            #..#.#....#..#.#..#.###..###..#....#....\n\
+            ###..#....###..####.#....#..#.#....#....\n\
+            #....#....#.#..#..#.#....#..#.#..#.#....\n\
+            #....####.#..#.#..#.#....###...##..####.\n\
+            \n";
+        assert_eq!(read_str(screen), Ok("PLRAFBCL".to_string()));
+
+        let grid = Array2::from_shape_fn((6, 4), |(y, x)| {
+            SMALL[0].1.lines().nth(y).unwrap().as_bytes()[x] == b'#'
+        });
+        assert_eq!(read(&grid), Ok("A".to_string()));
+        assert_eq!(
+            read_answer(&Answer::art(screen.lines())),
+            Ok(Answer::text("PLRAFBCL"))
+        );
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!(read_str("...\n..."), Err(Error::Empty));
+        assert_eq!(read_str("#\n#\n#"), Err(Error::UnknownHeight(3)));
+        let odd = render(SMALL, "HA", 1).replacen("####", "#.##", 1);
+        assert!(matches!(
+            read_str(&odd),
+            Err(Error::UnknownGlyph { index: 0, .. })
+        ));
+    }
+}
@@ -1,4 +1,16 @@
-use num::{traits::NumOps, One, Signed, Unsigned, Zero};
+pub mod combinators;
+
+use num::{traits::NumOps, One, PrimInt, Signed, Unsigned, Zero};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, thiserror::Error)]
+pub enum ParseIntError {
+    #[error("cannot parse integer from empty input")]
+    Empty,
+    #[error("invalid digit {1:?} at offset {0}")]
+    InvalidDigit(usize, char),
+    #[error("integer does not fit in the target type")]
+    Overflow,
+}
 
 #[must_use]
 pub fn parse_uint<N>(b: impl AsRef<[u8]>) -> N
@@ -16,11 +28,16 @@ where
     N: NumOps<isize, N> + NumOps<N> + Zero + One + From<u8> + Signed,
 {
     let mut b = b.as_ref();
-    let multiplier = if b.first() == Some(&b'-') {
-        b = &b[1..];
-        -N::one()
-    } else {
-        N::one()
+    let multiplier = match b.first() {
+        Some(b'-') => {
+            b = &b[1..];
+            -N::one()
+        }
+        Some(b'+') => {
+            b = &b[1..];
+            N::one()
+        }
+        _ => N::one(),
     };
     multiplier * b.iter().fold(N::zero(), |a, c| a * 10 + N::from(c & 0x0f))
 }
@@ -34,3 +51,218 @@ pub fn trim_ascii_whitespace(x: &[u8]) -> &[u8] {
     let to = x.iter().rposition(|x| !x.is_ascii_whitespace()).unwrap();
     &x[from..=to]
 }
+
+const ZEROES: u64 = u64::from_ne_bytes([b'0'; 8]);
+
+/// Whether all 8 bytes packed into `chunk` are ASCII digits.
+#[inline]
+fn all_digits(chunk: u64) -> bool {
+    let above = chunk.wrapping_add(u64::from_ne_bytes([0x46; 8]));
+    let below = chunk.wrapping_sub(ZEROES);
+    (chunk | above | below) & u64::from_ne_bytes([0x80; 8]) == 0
+}
+
+/// Parses exactly 8 ASCII digits in a single multiply-and-shift pass.
+#[inline]
+fn parse_8_digits(chunk: [u8; 8]) -> Option<u64> {
+    let chunk = u64::from_le_bytes(chunk);
+    if !all_digits(chunk) {
+        return None;
+    }
+    let v = chunk - ZEROES;
+    let v = (v * 10 + (v >> 8)) & 0x00ff_00ff_00ff_00ff;
+    let v = (v * 100 + (v >> 16)) & 0x0000_ffff_0000_ffff;
+    Some((v * 10_000 + (v >> 32)) & 0xffff_ffff)
+}
+
+#[inline]
+fn parse_16_digits(chunk: &[u8]) -> Option<u64> {
+    let high = parse_8_digits(chunk[..8].try_into().unwrap())?;
+    let low = parse_8_digits(chunk[8..16].try_into().unwrap())?;
+    Some(high * 100_000_000 + low)
+}
+
+/// Checked version of [`parse_int`]/[`parse_uint`]: rejects empty input, non-digits and values
+/// that don't fit in `N`. A leading `+` is always allowed, a leading `-` only for signed types.
+pub fn try_parse_int<N>(b: impl AsRef<[u8]>) -> Result<N, ParseIntError>
+where
+    N: PrimInt,
+{
+    let b = b.as_ref();
+    let (negative, digits, start) = match b.first() {
+        Some(b'-') if N::min_value() < N::zero() => (true, &b[1..], 1),
+        Some(b'+') => (false, &b[1..], 1),
+        _ => (false, b, 0),
+    };
+    if digits.is_empty() {
+        return Err(ParseIntError::Empty);
+    }
+
+    let invalid = |digits: &[u8], offset: usize| {
+        let (i, &c) = digits
+            .iter()
+            .enumerate()
+            .find(|(_, c)| !c.is_ascii_digit())
+            .expect("chunk has a non-digit");
+        ParseIntError::InvalidDigit(start + offset + i, c as char)
+    };
+    // Folds a chunk of digits worth `value` into the accumulator, subtracting for negative
+    // numbers so that `N::MIN` doesn't overflow on the way.
+    let push = |acc: N, value: u64, scale: u64| -> Result<N, ParseIntError> {
+        let scaled = acc
+            .checked_mul(&N::from(scale).ok_or(ParseIntError::Overflow)?)
+            .ok_or(ParseIntError::Overflow)?;
+        let value = N::from(value).ok_or(ParseIntError::Overflow)?;
+        if negative {
+            scaled.checked_sub(&value)
+        } else {
+            scaled.checked_add(&value)
+        }
+        .ok_or(ParseIntError::Overflow)
+    };
+
+    let mut acc = N::zero();
+    let mut rest = digits;
+    // The chunked paths need 10^8 to be representable.
+    if std::mem::size_of::<N>() >= 4 {
+        while rest.len() >= 16 && std::mem::size_of::<N>() >= 8 {
+            let value = parse_16_digits(rest)
+                .ok_or_else(|| invalid(&rest[..16], digits.len() - rest.len()))?;
+            acc = push(acc, value, 10_000_000_000_000_000)?;
+            rest = &rest[16..];
+        }
+        while rest.len() >= 8 {
+            let value = parse_8_digits(rest[..8].try_into().unwrap())
+                .ok_or_else(|| invalid(&rest[..8], digits.len() - rest.len()))?;
+            acc = push(acc, value, 100_000_000)?;
+            rest = &rest[8..];
+        }
+    }
+    for (i, &c) in rest.iter().enumerate() {
+        if !c.is_ascii_digit() {
+            return Err(ParseIntError::InvalidDigit(
+                start + digits.len() - rest.len() + i,
+                c as char,
+            ));
+        }
+        acc = push(acc, u64::from(c - b'0'), 10)?;
+    }
+    Ok(acc)
+}
+
+/// Checked version of [`parse_uint`].
+pub fn try_parse_uint<N>(b: impl AsRef<[u8]>) -> Result<N, ParseIntError>
+where
+    N: PrimInt + Unsigned,
+{
+    try_parse_int(b)
+}
+
+/// Every integer in `line`, in order, ignoring whatever is between them. For signed `N`, a `-`
+/// directly in front of a number makes it negative; for unsigned `N` it's a separator, so ranges
+/// like `2-4` read as two numbers.
+pub fn ints<N>(line: &[u8]) -> impl Iterator<Item = Result<N, ParseIntError>> + '_
+where
+    N: PrimInt,
+{
+    let mut pos = 0;
+    std::iter::from_fn(move || {
+        let offset = line[pos..].iter().position(u8::is_ascii_digit)?;
+        let mut start = pos + offset;
+        let end = line[start..]
+            .iter()
+            .position(|c| !c.is_ascii_digit())
+            .map_or(line.len(), |len| start + len);
+        if N::min_value() < N::zero() && start > 0 && line[start - 1] == b'-' {
+            start -= 1;
+        }
+        pos = end;
+        Some(try_parse_int(&line[start..end]))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn plus_sign() {
+        assert_eq!(parse_int::<isize>("+42"), 42);
+        assert_eq!(parse_int::<isize>("-42"), -42);
+    }
+
+    #[test]
+    fn checked() {
+        assert_eq!(try_parse_uint::<u64>("0"), Ok(0));
+        assert_eq!(try_parse_uint::<u64>("+12"), Ok(12));
+        assert_eq!(try_parse_uint::<u64>(""), Err(ParseIntError::Empty));
+        assert_eq!(try_parse_uint::<u64>("+"), Err(ParseIntError::Empty));
+        assert_eq!(
+            try_parse_uint::<u64>("-1"),
+            Err(ParseIntError::InvalidDigit(0, '-'))
+        );
+        assert_eq!(
+            try_parse_uint::<u8>("12a"),
+            Err(ParseIntError::InvalidDigit(2, 'a'))
+        );
+        assert_eq!(try_parse_uint::<u8>("255"), Ok(255));
+        assert_eq!(try_parse_uint::<u8>("256"), Err(ParseIntError::Overflow));
+        assert_eq!(try_parse_int::<i8>("-128"), Ok(-128));
+        assert_eq!(try_parse_int::<i8>("128"), Err(ParseIntError::Overflow));
+    }
+
+    #[test]
+    fn chunked() {
+        for n in [
+            12_345_678u64,
+            123_456_789,
+            1_234_567_890_123_456,
+            12_345_678_901_234_567,
+            u64::MAX,
+        ] {
+            assert_eq!(try_parse_uint::<u64>(n.to_string()), Ok(n));
+            assert_eq!(try_parse_int::<i128>(format!("-{n}")), Ok(-(n as i128)));
+        }
+        assert_eq!(try_parse_int::<i64>(i64::MIN.to_string()), Ok(i64::MIN));
+        assert_eq!(try_parse_int::<i64>(i64::MAX.to_string()), Ok(i64::MAX));
+        assert_eq!(
+            try_parse_uint::<u64>("18446744073709551616"),
+            Err(ParseIntError::Overflow)
+        );
+        assert_eq!(
+            try_parse_uint::<u32>("1234x678"),
+            Err(ParseIntError::InvalidDigit(4, 'x'))
+        );
+        assert_eq!(
+            try_parse_uint::<u64>("+12345678901234x67"),
+            Err(ParseIntError::InvalidDigit(15, 'x'))
+        );
+        assert_eq!(try_parse_uint::<u32>("00000000000000000007"), Ok(7));
+    }
+
+    #[test]
+    fn all_ints() {
+        let line = b"p=0,4 v=-3,+3 x-5 end";
+        assert_eq!(
+            ints::<isize>(line).collect::<Result<Vec<_>, _>>(),
+            Ok(vec![0, 4, -3, 3, -5])
+        );
+        assert_eq!(
+            ints::<u8>(b"1 300").nth(1),
+            Some(Err(ParseIntError::Overflow))
+        );
+        assert_eq!(ints::<i32>(b"no numbers").next(), None);
+    }
+
+    #[test]
+    fn unsigned_ranges() {
+        assert_eq!(
+            ints::<usize>(b"2-4,6-8").collect::<Result<Vec<_>, _>>(),
+            Ok(vec![2, 4, 6, 8])
+        );
+        assert_eq!(
+            ints::<u64>(b"-7 x-12").collect::<Result<Vec<_>, _>>(),
+            Ok(vec![7, 12])
+        );
+    }
+}
@@ -0,0 +1,245 @@
+//! Ready-made winnow parsers for the input shapes that keep coming up.
+use ndarray::Array2;
+use winnow::{
+    ascii::{dec_int, dec_uint, line_ending, space0, space1, Int, Uint},
+    combinator::{opt, preceded, repeat, separated, separated_pair, terminated},
+    stream::{Accumulate, AsBStr, AsChar, Compare, Stream, StreamIsPartial},
+    token::one_of,
+    PResult, Parser,
+};
+
+use crate::{collections::HVec, math::coord::Coord};
+
+/// The stream bounds every text puzzle parser needs, so they don't have to be spelled out on
+/// each function.
+pub trait TextStream:
+    Stream<Token: AsChar + Clone, Slice: AsBStr> + StreamIsPartial + for<'a> Compare<&'a str>
+{
+}
+
+impl<S> TextStream for S where
+    S: Stream<Token: AsChar + Clone, Slice: AsBStr> + StreamIsPartial + for<'a> Compare<&'a str>
+{
+}
+
+/// A signed integer, with an optional leading `+` or `-`.
+pub fn int<S, N>(input: &mut S) -> PResult<N>
+where
+    S: TextStream,
+    N: Int,
+{
+    dec_int.parse_next(input)
+}
+
+pub fn uint<S, N>(input: &mut S) -> PResult<N>
+where
+    S: TextStream,
+    N: Uint,
+{
+    dec_uint.parse_next(input)
+}
+
+/// Integers separated by spaces or tabs, like `7 6 4 2 1`.
+pub fn space_separated<S, N, C>(input: &mut S) -> PResult<C>
+where
+    S: TextStream,
+    N: Int,
+    C: Accumulate<N>,
+{
+    preceded(space0, separated(1.., int::<S, N>, space1)).parse_next(input)
+}
+
+/// Integers separated by commas, with optional spaces after each comma, like `3,4, 5`.
+pub fn comma_separated<S, N, C>(input: &mut S) -> PResult<C>
+where
+    S: TextStream,
+    N: Int,
+    C: Accumulate<N>,
+{
+    separated(1.., int::<S, N>, (",", space0)).parse_next(input)
+}
+
+/// A line of single digits with no separator, like `12345`.
+pub fn digit_line<S, C>(input: &mut S) -> PResult<C>
+where
+    S: TextStream,
+    C: Accumulate<u8>,
+{
+    repeat(
+        1..,
+        one_of(|t: S::Token| t.as_char().is_ascii_digit())
+            .map(|t: S::Token| t.as_char() as u8 - b'0'),
+    )
+    .parse_next(input)
+}
+
+/// A pair of integers like `1,2`.
+pub fn coord<S>(input: &mut S) -> PResult<Coord>
+where
+    S: TextStream,
+{
+    separated_pair(int, (",", space0), int)
+        .map(|(x, y)| Coord(x, y))
+        .parse_next(input)
+}
+
+/// A labeled pair of integers like `p=1,2`.
+pub fn labeled_coord<S>(label: &'static str) -> impl Parser<S, Coord, winnow::error::ContextError>
+where
+    S: TextStream,
+{
+    preceded((label, "="), coord)
+}
+
+/// Every item produced by `line` on its own line. The last line ending is optional.
+pub fn lines<S, O, C, P>(line: P) -> impl Parser<S, C, winnow::error::ContextError>
+where
+    S: TextStream,
+    C: Accumulate<O>,
+    P: Parser<S, O, winnow::error::ContextError>,
+{
+    repeat(1.., terminated(line, opt(line_ending)))
+}
+
+/// Blocks of lines separated by a blank line. `section` is expected to consume the line ending
+/// of its own last line, the way a parser built from [`lines`] does.
+pub fn sections<S, O, C, P>(section: P) -> impl Parser<S, C, winnow::error::ContextError>
+where
+    S: TextStream,
+    C: Accumulate<O>,
+    P: Parser<S, O, winnow::error::ContextError>,
+{
+    separated(1.., section, line_ending)
+}
+
+/// A `key: value` line, allowing spaces around the separator.
+pub fn key_value<S, K, V, PK, PV>(
+    key: PK,
+    sep: &'static str,
+    value: PV,
+) -> impl Parser<S, (K, V), winnow::error::ContextError>
+where
+    S: TextStream,
+    PK: Parser<S, K, winnow::error::ContextError>,
+    PV: Parser<S, V, winnow::error::ContextError>,
+{
+    separated_pair(key, (space0, sep, space0), value)
+}
+
+/// Up to `N` items separated by `sep`, kept on the stack.
+pub fn heapless_list<S, O, P, const N: usize>(
+    item: P,
+    sep: &'static str,
+) -> impl Parser<S, HVec<O, N>, winnow::error::ContextError>
+where
+    S: TextStream,
+    O: std::fmt::Debug,
+    P: Parser<S, O, winnow::error::ContextError>,
+{
+    separated(1..=N, item, sep)
+}
+
+/// A rectangular grid with one `cell` per character, rows separated by line endings.
+///
+/// Fails if the rows don't all have the same width.
+pub fn grid<S, T, P>(cell: P) -> impl Parser<S, Array2<T>, winnow::error::ContextError>
+where
+    S: TextStream,
+    P: Parser<S, T, winnow::error::ContextError>,
+{
+    lines::<S, Vec<T>, Vec<Vec<T>>, _>(repeat(1.., cell)).verify_map(|rows: Vec<Vec<T>>| {
+        let width = rows.first()?.len();
+        let height = rows.len();
+        if rows.iter().any(|r| r.len() != width) {
+            return None;
+        }
+        Array2::from_shape_vec((height, width), rows.into_iter().flatten().collect()).ok()
+    })
+}
+
+/// A rectangular grid of characters, mapped to cells through `f`. Characters that `f` rejects
+/// fail the parse.
+pub fn char_grid<S, T, F>(f: F) -> impl Parser<S, Array2<T>, winnow::error::ContextError>
+where
+    S: TextStream,
+    F: Fn(char) -> Option<T>,
+{
+    grid(
+        one_of(|t: S::Token| !matches!(t.as_char(), '\n' | '\r'))
+            .verify_map(move |t: S::Token| f(t.as_char())),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use winnow::{Located, Parser};
+
+    use super::*;
+
+    #[test]
+    fn numbers() {
+        assert_eq!(int::<_, isize>.parse("+12"), Ok(12));
+        assert_eq!(int::<_, isize>.parse("-12"), Ok(-12));
+        assert_eq!(
+            space_separated::<_, i32, Vec<_>>.parse("  7 6\t4"),
+            Ok(vec![7, 6, 4])
+        );
+        assert_eq!(
+            comma_separated::<_, i32, Vec<_>>.parse("3,4, -5"),
+            Ok(vec![3, 4, -5])
+        );
+        assert_eq!(digit_line::<_, Vec<_>>.parse("0123"), Ok(vec![0, 1, 2, 3]));
+    }
+
+    #[test]
+    fn coords() {
+        assert_eq!(coord.parse("1,-2"), Ok(Coord(1, -2)));
+        assert_eq!(
+            (labeled_coord("p"), " ", labeled_coord("v"))
+                .map(|(p, _, v)| (p, v))
+                .parse(Located::new("p=0,4 v=3,-3")),
+            Ok((Coord(0, 4), Coord(3, -3)))
+        );
+    }
+
+    #[test]
+    fn blocks() {
+        let input = "1,2\n3,4\n\n5,6\n";
+        let blocks: Vec<Vec<Coord>> = sections::<_, Vec<Coord>, _, _>(lines(coord))
+            .parse(input)
+            .unwrap();
+        assert_eq!(
+            blocks,
+            vec![vec![Coord(1, 2), Coord(3, 4)], vec![Coord(5, 6)]]
+        );
+
+        let kv: Vec<(&str, Vec<i32>)> = lines(key_value(
+            winnow::ascii::alpha1,
+            ":",
+            space_separated::<_, i32, _>,
+        ))
+        .parse("a: 1 2\nb :3\n")
+        .unwrap();
+        assert_eq!(kv, vec![("a", vec![1, 2]), ("b", vec![3])]);
+
+        let list: HVec<u8, 4> = heapless_list(uint, ",").parse("1,2,3").unwrap();
+        assert_eq!(&*list, &[1, 2, 3]);
+    }
+
+    #[test]
+    fn grids() {
+        let grid = char_grid(|c| match c {
+            '#' => Some(true),
+            '.' => Some(false),
+            _ => None,
+        })
+        .parse("#.\n.#\n")
+        .unwrap();
+        assert_eq!(grid.shape(), &[2, 2]);
+        assert!(grid[(1, 1)]);
+        assert!(!grid[(0, 1)]);
+
+        assert!(char_grid(Some).parse("ab\nc\n").is_err());
+        assert!(char_grid(|c| c.to_digit(10)).parse("12\n3x\n").is_err());
+    }
+}
@@ -0,0 +1,220 @@
+//! Progress reporting from inside a solution.
+//!
+//! A slow stage announces how much work it has and ticks it off, either with
+//! [`ProgressExt::progress`] on an iterator or through a [`Progress`] handle:
+//!
+//! ```
+//! use aoc_utils::progress::{Progress, ProgressExt};
+//!
+//! let total = (0..1000).progress().map(|i| i * 2).sum::<usize>();
+//!
+//! // Take the handle on the stage's thread, then it can be ticked from anywhere.
+//! let progress = Progress::current();
+//! progress.start(Some(10));
+//! (0..10).for_each(|_| progress.tick());
+//! ```
+//!
+//! The runner says which day and stage the calls belong to, and passes them on to the
+//! [`ProgressHook`] the binary installed with [`set_hook`]. Without one they do nothing.
+use std::{cell::Cell, sync::Mutex, sync::OnceLock};
+
+use hashbrown::HashMap;
+use tqdm::Pbar;
+
+use crate::Stage;
+
+/// Where progress ends up, usually a progress bar.
+pub trait ProgressHook: Send + Sync {
+    /// A stage has `total` steps to do, if it knows. Called again when it starts on new work.
+    fn start(&self, day: usize, stage: Stage, total: Option<usize>);
+    fn advance(&self, day: usize, stage: Stage, n: usize);
+    fn finish(&self, day: usize, stage: Stage);
+}
+
+static HOOK: OnceLock<Box<dyn ProgressHook>> = OnceLock::new();
+
+thread_local! {
+    static CURRENT: Cell<Option<(usize, Stage)>> = const { Cell::new(None) };
+}
+
+/// Installs the hook for the rest of the run. Fails if one is already installed.
+pub fn set_hook(hook: impl ProgressHook + 'static) -> miette::Result<()> {
+    HOOK.set(Box::new(hook))
+        .map_err(|_| miette::miette!("a progress hook is already installed"))
+}
+
+/// Marks the running thread as working on `stage` of `day` until the guard is dropped.
+pub(crate) fn enter(day: usize, stage: Stage) -> StageGuard {
+    CURRENT.set(Some((day, stage)));
+    StageGuard { day, stage }
+}
+
+pub(crate) struct StageGuard {
+    day: usize,
+    stage: Stage,
+}
+
+impl Drop for StageGuard {
+    fn drop(&mut self) {
+        CURRENT.set(None);
+        finish(self.day, self.stage);
+    }
+}
+
+/// Closes the stage's bar, for when the runner gives up on it.
+pub(crate) fn finish(day: usize, stage: Stage) {
+    if let Some(hook) = HOOK.get() {
+        hook.finish(day, stage);
+    }
+}
+
+/// A handle on the progress of one stage. It's `Copy`, so it can go into `rayon` closures.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Progress {
+    key: Option<(usize, Stage)>,
+}
+
+impl Progress {
+    /// The stage running on this thread, if there is one and a hook is installed.
+    pub fn current() -> Self {
+        Self {
+            key: HOOK.get().and_then(|_| CURRENT.get()),
+        }
+    }
+
+    fn with(&self, f: impl FnOnce(&dyn ProgressHook, usize, Stage)) {
+        if let (Some((day, stage)), Some(hook)) = (self.key, HOOK.get()) {
+            f(hook.as_ref(), day, stage);
+        }
+    }
+
+    pub fn start(&self, total: Option<usize>) {
+        self.with(|hook, day, stage| hook.start(day, stage, total));
+    }
+
+    pub fn advance(&self, n: usize) {
+        self.with(|hook, day, stage| hook.advance(day, stage, n));
+    }
+
+    pub fn tick(&self) {
+        self.advance(1);
+    }
+}
+
+/// An iterator that ticks its stage's progress for every item.
+pub struct Tracked<I> {
+    inner: I,
+    progress: Progress,
+}
+
+impl<I: Iterator> Iterator for Tracked<I> {
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let item = self.inner.next()?;
+        self.progress.tick();
+        Some(item)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+pub trait ProgressExt: Iterator + Sized {
+    /// Starts the current stage's progress at this iterator's length, and ticks it per item.
+    fn progress(self) -> Tracked<Self> {
+        let progress = Progress::current();
+        progress.start(self.size_hint().1);
+        Tracked {
+            inner: self,
+            progress,
+        }
+    }
+}
+
+impl<I: Iterator> ProgressExt for I {}
+
+/// Draws a `tqdm` bar on stderr for every stage that reports progress.
+#[derive(Default)]
+pub struct TqdmHook {
+    bars: Mutex<HashMap<(usize, Stage), Pbar>>,
+}
+
+impl ProgressHook for TqdmHook {
+    fn start(&self, day: usize, stage: Stage, total: Option<usize>) {
+        let mut bars = self.bars.lock().unwrap();
+        // Close the old bar before drawing the new one.
+        bars.remove(&(day, stage));
+        bars.insert((day, stage), tqdm::pbar(total));
+    }
+
+    fn advance(&self, day: usize, stage: Stage, n: usize) {
+        if let Some(bar) = self.bars.lock().unwrap().get_mut(&(day, stage)) {
+            let _ = bar.update(n);
+        }
+    }
+
+    fn finish(&self, day: usize, stage: Stage) {
+        self.bars.lock().unwrap().remove(&(day, stage));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Total, progress so far, and whether it's finished.
+    type Entry = (Option<usize>, usize, bool);
+
+    #[derive(Default)]
+    struct Counts(Mutex<HashMap<(usize, Stage), Entry>>);
+
+    impl ProgressHook for &'static Counts {
+        fn start(&self, day: usize, stage: Stage, total: Option<usize>) {
+            self.0
+                .lock()
+                .unwrap()
+                .insert((day, stage), (total, 0, false));
+        }
+
+        fn advance(&self, day: usize, stage: Stage, n: usize) {
+            self.0.lock().unwrap().get_mut(&(day, stage)).unwrap().1 += n;
+        }
+
+        fn finish(&self, day: usize, stage: Stage) {
+            if let Some(entry) = self.0.lock().unwrap().get_mut(&(day, stage)) {
+                entry.2 = true;
+            }
+        }
+    }
+
+    #[test]
+    fn reports_to_the_running_stage() {
+        let counts: &'static Counts = Box::leak(Box::default());
+        set_hook(counts).unwrap();
+        assert!(set_hook(TqdmHook::default()).is_err());
+
+        // Nothing is running on this thread yet.
+        (0..5).progress().for_each(drop);
+        assert!(counts.0.lock().unwrap().is_empty());
+
+        {
+            let _guard = enter(3, Stage::Part1);
+            assert_eq!((0..5).progress().count(), 5);
+            let progress = Progress::current();
+            std::thread::scope(|s| {
+                s.spawn(|| progress.advance(2));
+            });
+            assert_eq!(
+                counts.0.lock().unwrap()[&(3, Stage::Part1)],
+                (Some(5), 7, false)
+            );
+        }
+        assert_eq!(
+            counts.0.lock().unwrap()[&(3, Stage::Part1)],
+            (Some(5), 7, true)
+        );
+        assert_eq!(Progress::current(), Progress { key: None });
+    }
+}
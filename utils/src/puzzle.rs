@@ -0,0 +1,224 @@
+//! Reads the examples out of a puzzle's page, so that sample tests don't have to be copied by hand.
+//!
+//! Every part is an `<article class="day-desc">`. Its examples are the `<pre><code>` blocks, and
+//! the example answers are the `<code><em>` spans the text ends on. Once a part is solved, the page
+//! also has `Your puzzle answer was <code>...</code>` after it. Part 2 only shows up on pages
+//! fetched with a session.
+use std::fmt::Write;
+
+use crate::answer::Answer;
+
+/// One part of the puzzle, as far as it's shown on the page.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Part {
+    /// The example inputs, in the order they appear.
+    pub samples: Vec<String>,
+    /// The emphasized values in the text. The last one is usually the example's answer.
+    pub emphasized: Vec<String>,
+    /// The answer that was submitted, if the part is solved.
+    pub answer: Option<Answer>,
+}
+
+impl Part {
+    pub fn sample_answer(&self) -> Option<&str> {
+        self.emphasized.last().map(String::as_str)
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Puzzle {
+    /// The title without the dashes, like `Day 1: Historian Hysteria`.
+    pub title: Option<String>,
+    pub parts: Vec<Part>,
+}
+
+/// The text between `start` and `end` for every match, with where it starts in `html`.
+fn between<'a>(html: &'a str, start: &str, end: &str) -> Vec<(usize, &'a str)> {
+    let mut found = Vec::new();
+    let mut rest = 0;
+    while let Some(open) = html[rest..].find(start) {
+        let from = rest + open + start.len();
+        let Some(close) = html[from..].find(end) else {
+            break;
+        };
+        found.push((from, &html[from..from + close]));
+        rest = from + close + end.len();
+    }
+    found
+}
+
+/// Drops the tags and decodes the entities AoC uses.
+fn text(html: &str) -> String {
+    let mut text = String::with_capacity(html.len());
+    let mut in_tag = false;
+    for c in html.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' if in_tag => in_tag = false,
+            c if !in_tag => text.push(c),
+            _ => {}
+        }
+    }
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&amp;", "&")
+}
+
+impl Puzzle {
+    pub fn parse(html: &str) -> Self {
+        let title = between(html, "<h2>", "</h2>").first().map(|(_, title)| {
+            text(title)
+                .trim_matches(|c| c == '-' || c == ' ')
+                .to_string()
+        });
+        let articles = between(html, "<article class=\"day-desc\">", "</article>");
+        let parts = articles
+            .iter()
+            .enumerate()
+            .map(|(i, &(start, article))| {
+                // The answer sits between this article and the next.
+                let next = articles.get(i + 1).map_or(html.len(), |&(next, _)| next);
+                let after = &html[start + article.len()..next];
+                let answer = between(after, "Your puzzle answer was <code>", "</code>")
+                    .first()
+                    .map(|(_, answer)| text(answer).parse().unwrap());
+                let samples = between(article, "<pre><code>", "</code></pre>")
+                    .into_iter()
+                    .map(|(_, sample)| text(sample))
+                    .collect();
+                // Examples can emphasize parts of themselves, so only look outside of them.
+                let prose = article
+                    .split("<pre><code>")
+                    .map(|chunk| chunk.split_once("</code></pre>").map_or(chunk, |(_, p)| p))
+                    .collect::<String>();
+                let emphasized = between(&prose, "<code><em>", "</em></code>")
+                    .into_iter()
+                    .map(|(_, value)| text(value))
+                    .collect();
+                Part {
+                    samples,
+                    emphasized,
+                    answer,
+                }
+            })
+            .collect();
+        Self { title, parts }
+    }
+
+    /// A `sample_case!` for the first example of each part, answered with the last emphasized
+    /// value. Part 2 usually reuses part 1's example. Whatever the page doesn't have is left as
+    /// `todo`, which won't compile until it's filled in.
+    pub fn sample_stub(&self) -> String {
+        let part = |i: usize| self.parts.get(i);
+        let sample = |i: usize| part(i).and_then(|p| p.samples.first());
+        let value = |i: usize| {
+            part(i)
+                .and_then(Part::sample_answer)
+                .map_or_else(|| "todo".to_string(), literal)
+        };
+        let input1 = sample(0);
+        let input2 = sample(1).or(input1);
+
+        let mut stub = String::from("    sample_case! {\n        sample1 =>\n");
+        if input1 == input2 {
+            writeln!(stub, "            input = {};", indoc(input1)).unwrap();
+            writeln!(stub, "            part1 = {};", value(0)).unwrap();
+            writeln!(stub, "            part2 = {};", value(1)).unwrap();
+        } else {
+            writeln!(stub, "            input1 = {};", indoc(input1)).unwrap();
+            writeln!(stub, "            part1 = {};", value(0)).unwrap();
+            writeln!(stub, "            input2 = {};", indoc(input2)).unwrap();
+            writeln!(stub, "            part2 = {};", value(1)).unwrap();
+        }
+        stub.push_str("    }\n");
+        stub
+    }
+}
+
+/// Numbers as they are, anything else as a string.
+fn literal(value: &str) -> String {
+    if value.parse::<i128>().is_ok() {
+        value.to_string()
+    } else {
+        format!("{value:?}")
+    }
+}
+
+fn indoc(sample: Option<&String>) -> String {
+    let Some(sample) = sample else {
+        return "\"todo\"".to_string();
+    };
+    let mut block = String::from("indoc::indoc! {\"\n");
+    for line in sample.lines() {
+        let line = line.replace('\\', "\\\\").replace('"', "\\\"");
+        writeln!(block, "                {line}").unwrap();
+    }
+    block.push_str("            \"}");
+    block
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ANONYMOUS: &str = include_str!("../fixtures/puzzle/2024-day01.html");
+    const SOLVED: &str = include_str!("../fixtures/puzzle/2024-day01-solved.html");
+
+    #[test]
+    fn anonymous_page() {
+        let puzzle = Puzzle::parse(ANONYMOUS);
+        assert_eq!(puzzle.title.as_deref(), Some("Day 1: Historian Hysteria"));
+        assert_eq!(puzzle.parts.len(), 1);
+        let part = &puzzle.parts[0];
+        assert_eq!(part.samples, ["3   4\n4   3\n2   5\n1   3\n3   9\n3   3\n"]);
+        assert_eq!(part.sample_answer(), Some("11"));
+        assert_eq!(part.answer, None);
+    }
+
+    #[test]
+    fn solved_page() {
+        let puzzle = Puzzle::parse(SOLVED);
+        assert_eq!(puzzle.parts.len(), 2);
+        assert_eq!(puzzle.parts[0].answer, Some(Answer::Number(1_970_720)));
+        assert_eq!(puzzle.parts[1].answer, Some(Answer::Number(17_191_599)));
+        assert_eq!(puzzle.parts[1].samples, puzzle.parts[0].samples);
+        assert_eq!(puzzle.parts[1].sample_answer(), Some("31"));
+    }
+
+    #[test]
+    fn markup() {
+        let html = "<article class=\"day-desc\"><h2>--- Day 9: Odd &amp; Ends ---</h2>\
+            <pre><code><em>a</em> -&gt; &quot;b&quot;\n</code></pre>\
+            <p>It comes to <code><em>b-a</em></code>.</p></article>";
+        let puzzle = Puzzle::parse(html);
+        assert_eq!(puzzle.title.as_deref(), Some("Day 9: Odd & Ends"));
+        assert_eq!(puzzle.parts[0].samples, ["a -> \"b\"\n"]);
+        assert_eq!(puzzle.parts[0].emphasized, ["b-a"]);
+    }
+
+    #[test]
+    fn stubs() {
+        let stub = Puzzle::parse(SOLVED).sample_stub();
+        assert_eq!(
+            stub,
+            "    sample_case! {
+        sample1 =>
+            input = indoc::indoc! {\"
+                3   4
+                4   3
+                2   5
+                1   3
+                3   9
+                3   3
+            \"};
+            part1 = 11;
+            part2 = 31;
+    }
+"
+        );
+        let stub = Puzzle::parse(ANONYMOUS).sample_stub();
+        assert!(stub.contains("part2 = todo;"));
+    }
+}
@@ -0,0 +1,439 @@
+//! Runs a year's days, each stage timed on its own, and keeps going when one of them fails.
+//!
+//! Days run on the rayon thread pool by default. Timings taken that way are noisy, so
+//! [`Schedule::Serial`] is there for runs whose numbers matter.
+//!
+//! Each day's stages run on a thread of their own, so that the runner can stop waiting when a
+//! stage goes over its time budget. There's no way to stop the thread itself: it's left to finish
+//! in the background, and its results are thrown away.
+use std::{
+    any::Any,
+    fmt::{self, Display},
+    panic::{catch_unwind, AssertUnwindSafe},
+    sync::mpsc::{self, RecvTimeoutError},
+    time::{Duration, Instant},
+};
+
+use rayon::prelude::*;
+use tracking_allocator::AllocationRegistry;
+
+use crate::{
+    answer::{Answer, ToAnswer},
+    config, load_input, ocr, progress,
+    utils::file::Fetch,
+    Runner, Stage, StageTime,
+};
+
+/// How a day went. Failures say which stage they came from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Status {
+    Ok,
+    Error(String),
+    Panic(String),
+    Timeout(String),
+}
+
+impl Status {
+    pub fn is_ok(&self) -> bool {
+        matches!(self, Self::Ok)
+    }
+}
+
+impl Display for Status {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Ok => write!(f, "ok"),
+            Self::Error(message) => write!(f, "error ({message})"),
+            Self::Panic(message) => write!(f, "panic ({message})"),
+            Self::Timeout(message) => write!(f, "timeout ({message})"),
+        }
+    }
+}
+
+/// The stages a day got through, and how it ended.
+#[derive(Debug, Clone)]
+pub struct DayReport {
+    pub day: usize,
+    pub times: Vec<StageTime>,
+    /// The answers to the parts that finished, in order.
+    pub answers: Vec<Answer>,
+    pub status: Status,
+}
+
+impl DayReport {
+    pub fn total(&self) -> Duration {
+        self.times.iter().map(|t| t.time).sum()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Schedule {
+    #[default]
+    Parallel,
+    /// One day at a time, for timings that aren't fighting over the CPU.
+    Serial,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct RunOptions {
+    pub schedule: Schedule,
+    /// Turn on allocation tracking while the stages run.
+    pub track: bool,
+    /// How long a stage may take, unless its day sets its own [`Runner::timeout`].
+    pub timeout: Option<Duration>,
+    /// When to download inputs.
+    pub fetch: Fetch,
+    /// Whose inputs to run, from `aoc.toml`. The default profile if `None`.
+    pub profile: Option<String>,
+}
+
+/// Reads a `--timeout` in seconds, which has to be a positive number small enough for a
+/// [`Duration`].
+pub fn parse_timeout(secs: &str) -> Result<Duration, String> {
+    let secs = secs.parse::<f64>().map_err(|e| e.to_string())?;
+    if secs.is_nan() || secs <= 0.0 {
+        return Err(format!("{secs} isn't a positive number of seconds"));
+    }
+    Duration::try_from_secs_f64(secs).map_err(|e| e.to_string())
+}
+
+/// Runs one day of `year`. `run_days!` builds a table of these.
+pub type DayFn = fn(usize, &RunOptions) -> DayReport;
+
+fn panic_message(payload: Box<dyn Any + Send>) -> String {
+    payload
+        .downcast_ref::<&str>()
+        .map(|s| s.to_string())
+        .or_else(|| payload.downcast_ref::<String>().cloned())
+        .unwrap_or_else(|| "unknown panic".to_string())
+}
+
+/// Times `f`, catching any panic.
+fn timed<R, Part1, Part2, T>(
+    stage: Stage,
+    f: impl FnOnce() -> miette::Result<T>,
+) -> Result<(T, StageTime), Status>
+where
+    R: Runner<Part1, Part2>,
+    Part1: ToAnswer,
+    Part2: ToAnswer,
+{
+    let _progress = progress::enter(R::day(), stage);
+    let now = Instant::now();
+    let result = catch_unwind(AssertUnwindSafe(f));
+    let elapsed = now.elapsed();
+    match result {
+        Ok(Ok(value)) => Ok((value, StageTime::new::<Part1, Part2, R>(elapsed, stage))),
+        Ok(Err(e)) => {
+            log::error!("Day {} {} failed: {e:?}", R::day(), stage.name());
+            Err(Status::Error(format!("{}: {e}", stage.name())))
+        }
+        Err(payload) => Err(Status::Panic(format!(
+            "{}: {}",
+            stage.name(),
+            panic_message(payload)
+        ))),
+    }
+}
+
+fn log_answer(day: usize, part: usize, answer: &Answer) {
+    if answer.is_multiline() {
+        let letters = ocr::read_answer(answer).map_or_else(|e| format!("({e})"), |a| a.to_string());
+        log::info!("Day {day} part {part} - {letters}\n{answer}");
+    } else {
+        log::info!("Day {day} part {part} - {answer}");
+    }
+}
+
+/// Runs the stages of `R` on `text`, stopping at the first that fails or runs out of time.
+pub fn run_input<R, Part1, Part2>(text: String, options: &RunOptions) -> DayReport
+where
+    R: Runner<Part1, Part2> + 'static,
+    Part1: ToAnswer + 'static,
+    Part2: ToAnswer + 'static,
+{
+    let day = R::day();
+    let (tx, rx) = mpsc::channel();
+    let worker = move || {
+        let stages = || -> Result<(), Status> {
+            let (input, time) = timed::<R, _, _, _>(Stage::GetInput, || R::get_input(&text))?;
+            if tx.send(Ok((time, None))).is_err() {
+                // The runner gave up on this day.
+                return Ok(());
+            }
+            let (output1, time) = timed::<R, _, _, _>(Stage::Part1, || R::part1(&input))?;
+            let answer = output1.to_answer();
+            log_answer(day, 1, &answer);
+            if tx.send(Ok((time, Some(answer)))).is_err() {
+                return Ok(());
+            }
+            let (output2, time) = timed::<R, _, _, _>(Stage::Part2, || R::part2(&input))?;
+            let answer = output2.to_answer();
+            log_answer(day, 2, &answer);
+            let _ = tx.send(Ok((time, Some(answer))));
+            Ok(())
+        };
+        if let Err(status) = stages() {
+            let _ = tx.send(Err(status));
+        }
+    };
+    if let Err(e) = std::thread::Builder::new()
+        .name(format!("day{day:02}"))
+        .spawn(worker)
+    {
+        return DayReport {
+            day,
+            times: Vec::new(),
+            answers: Vec::new(),
+            status: Status::Error(format!("couldn't start a thread: {e}")),
+        };
+    }
+
+    let budget = R::timeout().or(options.timeout);
+    if options.track {
+        AllocationRegistry::enable_tracking();
+    }
+    let mut times = Vec::with_capacity(3);
+    let mut answers = Vec::with_capacity(2);
+    let mut status = Status::Ok;
+    for stage in [Stage::GetInput, Stage::Part1, Stage::Part2] {
+        let received = match budget {
+            Some(budget) => rx.recv_timeout(budget),
+            None => rx.recv().map_err(RecvTimeoutError::from),
+        };
+        match received {
+            Ok(Ok((time, answer))) => {
+                times.push(time);
+                answers.extend(answer);
+            }
+            Ok(Err(failed)) => status = failed,
+            Err(RecvTimeoutError::Timeout) => {
+                progress::finish(day, stage);
+                status = Status::Timeout(format!(
+                    "{}: over {:?}",
+                    stage.name(),
+                    budget.unwrap_or_default()
+                ));
+            }
+            Err(RecvTimeoutError::Disconnected) => {
+                status = Status::Panic(format!("{}: the day's thread died", stage.name()));
+            }
+        }
+        if !status.is_ok() {
+            break;
+        }
+    }
+    if options.track {
+        AllocationRegistry::disable_tracking();
+    }
+    DayReport {
+        day,
+        times,
+        answers,
+        status,
+    }
+}
+
+/// Loads the input for `R` and runs it with [`run_input`].
+pub fn run_day<R, Part1, Part2>(year: usize, options: &RunOptions) -> DayReport
+where
+    R: Runner<Part1, Part2> + 'static,
+    Part1: ToAnswer + 'static,
+    Part2: ToAnswer + 'static,
+{
+    let day = R::day();
+    let comment = R::comment();
+    let comment = if comment.is_empty() {
+        String::new()
+    } else {
+        format!(" : {comment}")
+    };
+    log::info!("Day {day}{comment}");
+    let input = config::profile(options.profile.as_deref())
+        .and_then(|profile| load_input(&profile, year, day, options.fetch));
+    match input {
+        Ok(text) => run_input::<R, _, _>(text, options),
+        Err(e) => DayReport {
+            day,
+            times: Vec::new(),
+            answers: Vec::new(),
+            status: Status::Error(format!("input: {e}")),
+        },
+    }
+}
+
+/// Runs the chosen `days` out of `table`, or all of them if none were chosen. A day that isn't in
+/// the table gets an error report rather than stopping the run.
+pub fn run_selected(
+    year: usize,
+    table: &[(usize, DayFn)],
+    days: &[usize],
+    options: &RunOptions,
+) -> Vec<DayReport> {
+    let selected = if days.is_empty() {
+        table.iter().map(|&(day, f)| (day, Some(f))).collect()
+    } else {
+        days.iter()
+            .map(|&day| {
+                (
+                    day,
+                    table.iter().find(|(id, _)| *id == day).map(|&(_, f)| f),
+                )
+            })
+            .collect::<Vec<_>>()
+    };
+    let run = |&(day, f): &(usize, Option<DayFn>)| match f {
+        Some(f) => f(year, options),
+        None => DayReport {
+            day,
+            times: Vec::new(),
+            answers: Vec::new(),
+            status: Status::Error(format!("day {day} isn't in {year}")),
+        },
+    };
+    match options.schedule {
+        Schedule::Serial => selected.iter().map(run).collect(),
+        Schedule::Parallel => selected.par_iter().map(run).collect(),
+    }
+}
+
+/// Logs one line per day with its status and total time.
+pub fn log_summary(reports: &[DayReport]) {
+    log::info!("Summary:");
+    for report in reports {
+        let level = if report.status.is_ok() {
+            log::Level::Info
+        } else {
+            log::Level::Error
+        };
+        log::log!(
+            level,
+            "Day{:02}  {:?}  {}",
+            report.day,
+            report.total(),
+            report.status
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use miette::Result;
+
+    use super::*;
+
+    struct Panics;
+
+    impl Runner for Panics {
+        type Input<'input> = usize;
+
+        fn day() -> usize {
+            1
+        }
+
+        fn get_input(input: &str) -> Result<usize> {
+            Ok(input.len())
+        }
+
+        fn part1(input: &usize) -> Result<usize> {
+            Ok(*input)
+        }
+
+        fn part2(_: &usize) -> Result<usize> {
+            panic!("Reserved operand")
+        }
+    }
+
+    struct Slow;
+
+    impl Runner for Slow {
+        type Input<'input> = &'input str;
+
+        fn day() -> usize {
+            2
+        }
+
+        fn timeout() -> Option<Duration> {
+            Some(Duration::from_millis(50))
+        }
+
+        fn get_input(input: &str) -> Result<&str> {
+            Ok(input)
+        }
+
+        fn part1(_: &&str) -> Result<usize> {
+            std::thread::sleep(Duration::from_secs(2));
+            Ok(0)
+        }
+
+        fn part2(_: &&str) -> Result<usize> {
+            Ok(0)
+        }
+    }
+
+    #[test]
+    fn catches_failures() {
+        let report = run_input::<Panics, _, _>("abc".to_string(), &RunOptions::default());
+        assert_eq!(report.times.len(), 2);
+        assert_eq!(report.answers, [Answer::Number(3)]);
+        assert_eq!(
+            report.status,
+            Status::Panic("part2: Reserved operand".to_string())
+        );
+
+        let failed = timed::<Panics, _, _, usize>(Stage::Part1, || miette::bail!("no answer"));
+        assert_eq!(failed, Err(Status::Error("part1: no answer".to_string())));
+    }
+
+    #[test]
+    fn times_out() {
+        let options = RunOptions {
+            timeout: Some(Duration::from_secs(60)),
+            ..RunOptions::default()
+        };
+        let now = Instant::now();
+        let report = run_input::<Slow, _, _>(String::new(), &options);
+        assert!(now.elapsed() < Duration::from_secs(1));
+        assert_eq!(report.times.len(), 1);
+        assert_eq!(
+            report.status,
+            Status::Timeout("part1: over 50ms".to_string())
+        );
+    }
+
+    #[test]
+    fn timeouts() {
+        assert_eq!(parse_timeout("1.5"), Ok(Duration::from_millis(1500)));
+        for bad in ["0", "-1", "NaN", "inf", "1e30", "soon"] {
+            assert!(parse_timeout(bad).is_err(), "{bad}");
+        }
+    }
+
+    #[test]
+    fn unknown_days() {
+        fn ok(_: usize, _: &RunOptions) -> DayReport {
+            DayReport {
+                day: 1,
+                times: Vec::new(),
+                answers: Vec::new(),
+                status: Status::Ok,
+            }
+        }
+        let table: &[(usize, DayFn)] = &[(1, ok)];
+        for schedule in [Schedule::Serial, Schedule::Parallel] {
+            let options = RunOptions {
+                schedule,
+                ..RunOptions::default()
+            };
+            let reports = run_selected(2024, table, &[1, 30], &options);
+            assert_eq!(reports.len(), 2);
+            assert!(reports[0].status.is_ok());
+            assert_eq!(reports[1].day, 30);
+            assert!(matches!(reports[1].status, Status::Error(_)));
+        }
+        assert_eq!(
+            run_selected(2024, table, &[], &RunOptions::default()).len(),
+            1
+        );
+    }
+}
@@ -0,0 +1,227 @@
+//! Cycle detection for simulations that eventually repeat, so that step `N` can be found without
+//! running all `N` steps.
+use std::hash::Hash;
+
+use hashbrown::HashMap;
+
+/// The shape of the sequence `x0, step(x0), step(step(x0)), ...`: the first `mu` states are a
+/// tail that never comes back, after which the states repeat every `lambda` steps.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Cycle {
+    pub mu: usize,
+    pub lambda: usize,
+}
+
+impl Cycle {
+    /// The earliest step whose state is the same as the state at step `n`.
+    pub fn reduce(&self, n: usize) -> usize {
+        if n < self.mu {
+            n
+        } else {
+            self.mu + (n - self.mu) % self.lambda
+        }
+    }
+
+    /// Extrapolates a metric that grows by the same amount on every lap around the cycle, like the
+    /// height of a tower, from its values at the steps before `mu + lambda`.
+    pub fn extrapolate<F>(&self, n: usize, mut value_at: F) -> i64
+    where
+        F: FnMut(usize) -> i64,
+    {
+        if n < self.mu {
+            return value_at(n);
+        }
+        let laps = ((n - self.mu) / self.lambda) as i64;
+        let per_lap = value_at(self.mu + self.lambda) - value_at(self.mu);
+        value_at(self.reduce(n)) + laps * per_lap
+    }
+}
+
+/// Floyd's tortoise and hare. Only keeps two states around, but steps about three times as often
+/// as [`find_cycle`].
+///
+/// States are compared through `key`, which should drop anything that doesn't affect the future,
+/// like a running total. Never returns if the keys don't repeat.
+pub fn floyd<S, K, F, G>(initial: &S, mut step: F, mut key: G) -> Cycle
+where
+    S: Clone,
+    K: PartialEq,
+    F: FnMut(&S) -> S,
+    G: FnMut(&S) -> K,
+{
+    let mut tortoise = step(initial);
+    let mut hare = step(&tortoise);
+    while key(&tortoise) != key(&hare) {
+        tortoise = step(&tortoise);
+        let halfway = step(&hare);
+        hare = step(&halfway);
+    }
+
+    let mut mu = 0;
+    tortoise = initial.clone();
+    while key(&tortoise) != key(&hare) {
+        tortoise = step(&tortoise);
+        hare = step(&hare);
+        mu += 1;
+    }
+
+    let mut lambda = 1;
+    hare = step(&tortoise);
+    while key(&tortoise) != key(&hare) {
+        hare = step(&hare);
+        lambda += 1;
+    }
+    Cycle { mu, lambda }
+}
+
+/// Brent's algorithm. Same memory as [`floyd`], with fewer steps.
+pub fn brent<S, K, F, G>(initial: &S, mut step: F, mut key: G) -> Cycle
+where
+    S: Clone,
+    K: PartialEq,
+    F: FnMut(&S) -> S,
+    G: FnMut(&S) -> K,
+{
+    let mut power = 1;
+    let mut lambda = 1;
+    let mut tortoise = initial.clone();
+    let mut hare = step(initial);
+    while key(&tortoise) != key(&hare) {
+        if power == lambda {
+            tortoise = hare.clone();
+            power *= 2;
+            lambda = 0;
+        }
+        hare = step(&hare);
+        lambda += 1;
+    }
+
+    let mut mu = 0;
+    tortoise = initial.clone();
+    hare = (0..lambda).fold(initial.clone(), |s, _| step(&s));
+    while key(&tortoise) != key(&hare) {
+        tortoise = step(&tortoise);
+        hare = step(&hare);
+        mu += 1;
+    }
+    Cycle { mu, lambda }
+}
+
+/// A cycle found by [`find_cycle`], along with every state up to the start of its second lap.
+#[derive(Debug, Clone)]
+pub struct Detected<S> {
+    pub cycle: Cycle,
+    history: Vec<S>,
+}
+
+impl<S> Detected<S> {
+    /// The state at step `n`, without simulating anything.
+    pub fn state_at(&self, n: usize) -> &S {
+        &self.history[self.cycle.reduce(n)]
+    }
+
+    /// Every state seen, from the initial one up to `mu + lambda` inclusive.
+    pub fn history(&self) -> &[S] {
+        &self.history
+    }
+
+    /// [`Cycle::extrapolate`] of `metric` over the recorded states.
+    pub fn extrapolate<F>(&self, n: usize, mut metric: F) -> i64
+    where
+        F: FnMut(&S) -> i64,
+    {
+        self.cycle.extrapolate(n, |i| metric(&self.history[i]))
+    }
+}
+
+/// Finds the cycle by remembering the key of every state, and keeps the states so that any step
+/// can be looked up afterwards. Never returns if the keys don't repeat.
+pub fn find_cycle<S, K, F, G>(initial: S, mut step: F, mut key: G) -> Detected<S>
+where
+    K: Hash + Eq,
+    F: FnMut(&S) -> S,
+    G: FnMut(&S) -> K,
+{
+    let mut seen = HashMap::new();
+    let mut history = vec![initial];
+    loop {
+        let current = history.last().unwrap();
+        let at = history.len() - 1;
+        if let Some(&mu) = seen.get(&key(current)) {
+            return Detected {
+                cycle: Cycle {
+                    mu,
+                    lambda: at - mu,
+                },
+                history,
+            };
+        }
+        seen.insert(key(current), at);
+        let next = step(current);
+        history.push(next);
+    }
+}
+
+/// The state after `n` steps, skipping over every full lap of the cycle.
+pub fn fast_forward<S, K, F, G>(initial: S, n: usize, step: F, key: G) -> S
+where
+    S: Clone,
+    K: Hash + Eq,
+    F: FnMut(&S) -> S,
+    G: FnMut(&S) -> K,
+{
+    find_cycle(initial, step, key).state_at(n).clone()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn step(x: &u64) -> u64 {
+        (x * x + 1) % 255
+    }
+
+    fn naive(n: usize) -> u64 {
+        (0..n).fold(3, |x, _| step(&x))
+    }
+
+    #[test]
+    fn algorithms_agree() {
+        let detected = find_cycle(3, step, |&x| x);
+        let cycle = detected.cycle;
+        assert_eq!(floyd(&3, step, |&x| x), cycle);
+        assert_eq!(brent(&3, step, |&x| x), cycle);
+        assert_eq!(
+            detected.state_at(cycle.mu),
+            detected.state_at(cycle.mu + cycle.lambda)
+        );
+        for n in [0, 1, 5, 100, 12345] {
+            assert_eq!(*detected.state_at(n), naive(n));
+        }
+        assert_eq!(fast_forward(3, 999, step, |&x| x), naive(999));
+    }
+
+    #[test]
+    fn pure_cycle() {
+        let next = |x: &u8| (x + 1) % 7;
+        assert_eq!(floyd(&0, next, |&x| x), Cycle { mu: 0, lambda: 7 });
+        assert_eq!(brent(&0, next, |&x| x), Cycle { mu: 0, lambda: 7 });
+        assert_eq!(
+            find_cycle(0, next, |&x| x).cycle,
+            Cycle { mu: 0, lambda: 7 }
+        );
+    }
+
+    #[test]
+    fn extrapolate_growth() {
+        // A position that loops after a tail, with a height that keeps growing.
+        let next = |&(pos, height): &(u32, i64)| {
+            let pos = if pos < 3 { pos + 1 } else { 3 + (pos - 2) % 4 };
+            (pos, height + pos as i64)
+        };
+        let detected = find_cycle((0, 0), next, |&(pos, _)| pos);
+        assert_eq!(detected.cycle, Cycle { mu: 3, lambda: 4 });
+        let expected = (0..1_000).fold((0, 0), |s, _| next(&s)).1;
+        assert_eq!(detected.extrapolate(1_000, |&(_, height)| height), expected);
+    }
+}
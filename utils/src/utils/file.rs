@@ -1,92 +1,448 @@
+//! Finding the project root, and downloading inputs and puzzle pages from adventofcode.com.
+//!
+//! Downloads go through a [`Fetcher`], which identifies itself with a User-Agent, waits at least
+//! [`Fetcher::DEFAULT_INTERVAL`] between requests, and turns the site's error pages into an
+//! [`Error`] instead of saving them as the input.
 use miette::{IntoDiagnostic, Result, WrapErr};
 use reqwest::{
     blocking::Client,
-    header::{HeaderMap, HeaderValue, CONTENT_TYPE, COOKIE},
+    header::{HeaderMap, HeaderValue, COOKIE, USER_AGENT},
     redirect::Policy,
+    StatusCode,
 };
 use std::{
-    fs::{create_dir_all, OpenOptions},
-    io::Write,
+    fs::create_dir_all,
     path::{Path, PathBuf},
+    sync::Mutex,
+    time::{Duration, Instant},
 };
 
-pub fn download_input(
-    day: usize,
-    year: usize,
-    session: &str,
-    filename: impl AsRef<Path>,
-) -> Result<()> {
-    let text = fetch_input(year, day, session)?;
-
-    log::info!("Saving file: {}", filename.as_ref().display());
-    save_input(filename, text)?;
-    Ok(())
+use crate::config::CONFIG_FILE;
+
+/// The User-Agent without a contact: just the tool and its version.
+pub const DEFAULT_USER_AGENT: &str = concat!("aoc-utils/", env!("CARGO_PKG_VERSION"));
+
+/// Who's asking, as the site asks automated tools to say. `contact` is how to reach whoever runs
+/// this, like their repository or e-mail address, from `contact` in `aoc.toml` or
+/// `AOC_USER_AGENT`.
+pub fn user_agent(contact: Option<&str>) -> String {
+    match contact.map(str::trim).filter(|c| !c.is_empty()) {
+        Some(contact) => format!("{DEFAULT_USER_AGENT} ({contact})"),
+        None => DEFAULT_USER_AGENT.to_string(),
+    }
+}
+
+/// When [`load_or_fetch`] goes to the network.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Fetch {
+    /// Only for files that aren't cached yet.
+    #[default]
+    Missing,
+    /// Every time, replacing the cached file once the download succeeds.
+    Always,
+    /// Never; a file that isn't cached is an error.
+    Never,
 }
 
-fn save_input(filename: impl AsRef<Path>, text: String) -> Result<(), miette::Error> {
-    create_dir_all(
-        filename
-            .as_ref()
-            .parent()
-            .ok_or_else(|| miette::miette!("file {} has no parent", filename.as_ref().display()))?,
-    )
-    .into_diagnostic()?;
-    let _ = OpenOptions::new()
-        .write(true)
-        .create_new(true)
-        .open(filename)
-        .into_diagnostic()
-        .wrap_err("failed to open file")?
-        .write(text.as_bytes())
-        .into_diagnostic()
-        .wrap_err("failed to write to file")?;
-    Ok(())
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error, miette::Diagnostic)]
+pub enum Error {
+    #[error("AOCSESSION isn't set")]
+    #[diagnostic(help("copy the `session` cookie from adventofcode.com into .env"))]
+    NoSession,
+    #[error("the session was turned down, it has probably expired")]
+    #[diagnostic(help(
+        "log in to adventofcode.com again and copy the new `session` cookie into .env"
+    ))]
+    SessionExpired,
+    #[error("{0} isn't unlocked yet")]
+    NotUnlocked(String),
+    #[error("{0} doesn't exist")]
+    NotFound(String),
+    #[error("{url} answered {status}")]
+    Status { url: String, status: u16 },
+    #[error("request to {url} failed: {message}")]
+    Request { url: String, message: String },
+    #[error("{} isn't cached, and fetching is turned off", .0.display())]
+    #[diagnostic(help("run once without --offline to download it"))]
+    Offline(PathBuf),
+    #[error("failed to write {}: {message}", path.display())]
+    Write { path: PathBuf, message: String },
 }
 
-fn fetch_input(year: usize, day: usize, session: &str) -> Result<String, miette::Error> {
-    let url = format!("https://adventofcode.com/{year}/day/{day}/input");
-    log::info!("Downloading: {}", url);
-    let cookie_header = HeaderValue::from_str(&format!("session={}", session.trim()))
-        .into_diagnostic()
-        .wrap_err("invalid cookie header")?;
-    let content_header = HeaderValue::from_str("text/plain")
-        .into_diagnostic()
-        .wrap_err("invalid content header")?;
-    let mut headers = HeaderMap::new();
-    headers.insert(COOKIE, cookie_header);
-    headers.insert(CONTENT_TYPE, content_header);
-    let client = Client::builder()
-        .default_headers(headers)
-        .redirect(Policy::none())
-        .build()
-        .into_diagnostic()
-        .wrap_err("failed to build client")?;
-    let text = client
-        .get(&url)
-        .send()
-        .and_then(reqwest::blocking::Response::text)
-        .into_diagnostic()
-        .wrap_err("failed to download input")?;
-    Ok(text)
+/// When the last request went out, from any [`Fetcher`].
+static LAST_REQUEST: Mutex<Option<Instant>> = Mutex::new(None);
+
+/// Downloads from adventofcode.com, or whatever `base_url` says.
+#[derive(Debug, Clone)]
+pub struct Fetcher {
+    client: Client,
+    base_url: String,
+    session: Option<String>,
+    user_agent: HeaderValue,
+    interval: Duration,
+}
+
+impl Fetcher {
+    pub const BASE_URL: &str = "https://adventofcode.com";
+    pub const DEFAULT_INTERVAL: Duration = Duration::from_secs(2);
+
+    pub fn new(session: Option<&str>) -> Result<Self> {
+        let mut headers = HeaderMap::new();
+        let session = session.map(str::trim).filter(|s| !s.is_empty());
+        if let Some(session) = session {
+            let cookie_header = HeaderValue::from_str(&format!("session={session}"))
+                .into_diagnostic()
+                .wrap_err("invalid cookie header")?;
+            headers.insert(COOKIE, cookie_header);
+        }
+        // The site redirects to the login page rather than answering, so a redirect is an answer.
+        let client = Client::builder()
+            .default_headers(headers)
+            .redirect(Policy::none())
+            .build()
+            .into_diagnostic()
+            .wrap_err("failed to build client")?;
+        Ok(Self {
+            client,
+            base_url: Self::BASE_URL.to_string(),
+            session: session.map(str::to_string),
+            user_agent: HeaderValue::from_static(DEFAULT_USER_AGENT),
+            interval: Self::DEFAULT_INTERVAL,
+        })
+    }
+
+    /// A fetcher with the session in `AOCSESSION` and the contact in `AOC_USER_AGENT`, if they're
+    /// set.
+    pub fn from_env() -> Result<Self> {
+        Self::new(std::env::var("AOCSESSION").ok().as_deref())?
+            .with_contact(std::env::var("AOC_USER_AGENT").ok().as_deref())
+    }
+
+    /// Adds `contact` to the User-Agent; see [`user_agent`].
+    pub fn with_contact(mut self, contact: Option<&str>) -> Result<Self> {
+        self.user_agent = HeaderValue::from_str(&user_agent(contact))
+            .into_diagnostic()
+            .wrap_err("invalid contact for the User-Agent")?;
+        Ok(self)
+    }
+
+    pub fn with_base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.base_url = base_url.into();
+        self
+    }
+
+    /// The least time between two requests.
+    pub fn with_interval(mut self, interval: Duration) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    pub fn has_session(&self) -> bool {
+        self.session.is_some()
+    }
+
+    /// Waits until `interval` has passed since the last request.
+    fn throttle(&self) {
+        let mut last = LAST_REQUEST.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(wait) =
+            last.and_then(|last| (last + self.interval).checked_duration_since(Instant::now()))
+        {
+            log::debug!("Waiting {wait:?} before the next request");
+            std::thread::sleep(wait);
+        }
+        *last = Some(Instant::now());
+    }
+
+    /// Gets `path` and checks the answer is what was asked for.
+    pub fn get(&self, path: &str) -> Result<String, Error> {
+        let url = format!("{}{path}", self.base_url);
+        self.throttle();
+        log::info!("Downloading: {}", url);
+        let request_failed = |e: reqwest::Error| Error::Request {
+            url: url.clone(),
+            message: e.to_string(),
+        };
+        let response = self
+            .client
+            .get(&url)
+            .header(USER_AGENT, &self.user_agent)
+            .send()
+            .map_err(request_failed)?;
+        let status = response.status();
+        let body = response.text().map_err(request_failed)?;
+        match status {
+            _ if status.is_redirection() => Err(Error::SessionExpired),
+            StatusCode::BAD_REQUEST | StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN => {
+                Err(Error::SessionExpired)
+            }
+            StatusCode::NOT_FOUND if body.contains("before it unlocks") => {
+                Err(Error::NotUnlocked(url))
+            }
+            StatusCode::NOT_FOUND => Err(Error::NotFound(url)),
+            // Just in case the login page ever comes back as a success.
+            _ if status.is_success() && body.contains("Please log in") => {
+                Err(Error::SessionExpired)
+            }
+            _ if status.is_success() => Ok(body),
+            _ => Err(Error::Status {
+                url,
+                status: status.as_u16(),
+            }),
+        }
+    }
+
+    pub fn input(&self, year: usize, day: usize) -> Result<String, Error> {
+        if !self.has_session() {
+            return Err(Error::NoSession);
+        }
+        self.get(&format!("/{year}/day/{day}/input"))
+    }
+
+    /// The JSON behind private leaderboard `id`, which needs a session that can see it.
+    pub fn leaderboard(&self, year: usize, id: u64) -> Result<String, Error> {
+        if !self.has_session() {
+            return Err(Error::NoSession);
+        }
+        self.get(&format!("/{year}/leaderboard/private/view/{id}.json"))
+    }
+
+    /// The puzzle's page. Part 2 is only on it when there's a session.
+    pub fn puzzle(&self, year: usize, day: usize) -> Result<String, Error> {
+        self.get(&format!("/{year}/day/{day}"))
+    }
+}
+
+/// Replaces `path` with `text` in one go, so that it's never left half written.
+pub fn write_atomic(path: &Path, text: &str) -> Result<(), Error> {
+    let failed = |e: std::io::Error| Error::Write {
+        path: path.to_path_buf(),
+        message: e.to_string(),
+    };
+    if let Some(parent) = path.parent() {
+        create_dir_all(parent).map_err(failed)?;
+    }
+    let mut temp = path.as_os_str().to_owned();
+    temp.push(".part");
+    let temp = PathBuf::from(temp);
+    std::fs::write(&temp, text).map_err(failed)?;
+    std::fs::rename(&temp, path).map_err(|e| {
+        let _ = std::fs::remove_file(&temp);
+        failed(e)
+    })
 }
 
+/// Reads `path`, downloading it with `download` first when `fetch` says to.
+pub fn load_or_fetch(
+    path: &Path,
+    fetch: Fetch,
+    fetcher: &Fetcher,
+    download: impl FnOnce(&Fetcher) -> Result<String, Error>,
+) -> Result<String> {
+    match (fetch, path.exists()) {
+        (Fetch::Never, false) => Err(Error::Offline(path.to_path_buf()))?,
+        (Fetch::Never | Fetch::Missing, true) => std::fs::read_to_string(path)
+            .into_diagnostic()
+            .wrap_err_with(|| format!("reading {}", path.display())),
+        (Fetch::Missing | Fetch::Always, _) => {
+            let text = download(fetcher)?;
+            log::info!("Saving file: {}", path.display());
+            write_atomic(path, &text)?;
+            Ok(text)
+        }
+    }
+}
+
+/// The directory holding `aoc.toml`, or else the `.env` file, which inputs and baselines are kept
+/// relative to. Without either, it's the current directory.
+pub fn project_root() -> Result<PathBuf, miette::Report> {
+    // The variables in `.env` are wanted either way.
+    let env_path = dotenv::dotenv().ok();
+    let current = std::env::current_dir().into_diagnostic()?;
+    let root = current
+        .ancestors()
+        .find(|dir| dir.join(CONFIG_FILE).exists())
+        .or_else(|| env_path.as_deref().and_then(Path::parent))
+        .unwrap_or(&current)
+        .to_path_buf();
+    log::debug!("Project root is {}", root.display());
+    Ok(root)
+}
+
+/// Where the default profile's input for `day` is kept.
 pub fn get_input_path(year: usize, day: usize) -> Result<PathBuf, miette::Report> {
-    let input_path = PathBuf::from("input")
-        .join(year.to_string())
-        .join(format!("day{day:02}.txt"));
-    let env_path = dotenv::dotenv()
-        .into_diagnostic()
-        .wrap_err("loading .env file")?;
-    let parent_path = env_path
-        .parent()
-        .ok_or_else(|| miette::miette!("no parent path for .env file"))?;
-    log::debug!("Loaded .env file from {}", parent_path.display());
-    let input_full_path = if input_path.exists() {
-        input_path
-    } else {
-        let joined_path = parent_path.join(&input_path);
-        log::debug!("Joined path: {}", joined_path.display());
-        joined_path.clone()
+    Ok(crate::config::profile(None)?.input_path(year, day))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        io::{BufRead, BufReader, Write},
+        net::TcpListener,
+        sync::Arc,
     };
-    Ok(input_full_path)
+
+    use super::*;
+
+    type Requests = Arc<Mutex<Vec<String>>>;
+
+    /// A server on localhost answering each path with its `(status line, body)`, and 404 for
+    /// anything else. Returns its URL and the requests it got, headers and all.
+    fn stub_server(routes: &[(&str, &str, &str)]) -> (String, Requests) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let routes = routes
+            .iter()
+            .map(|&(path, status, body)| (path.to_string(), status.to_string(), body.to_string()))
+            .collect::<Vec<_>>();
+        let requests = Requests::default();
+        let seen = requests.clone();
+        std::thread::spawn(move || {
+            for stream in listener.incoming() {
+                let mut stream = stream.unwrap();
+                let mut request = String::new();
+                let mut reader = BufReader::new(&stream);
+                while reader.read_line(&mut request).unwrap() > 2 {}
+                seen.lock().unwrap().push(request.to_lowercase());
+                let path = request.split_whitespace().nth(1).unwrap_or_default();
+                let (status, body) = routes
+                    .iter()
+                    .find(|(p, _, _)| p == path)
+                    .map_or(("404 Not Found", "404 Not Found"), |(_, s, b)| (s, b));
+                write!(
+                    stream,
+                    "HTTP/1.1 {status}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                    body.len()
+                )
+                .unwrap();
+            }
+        });
+        (url, requests)
+    }
+
+    fn fetcher(url: &str, session: Option<&str>) -> Fetcher {
+        Fetcher::new(session)
+            .unwrap()
+            .with_base_url(url)
+            .with_interval(Duration::ZERO)
+    }
+
+    fn scratch(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("aoc-utils-{name}-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        dir
+    }
+
+    #[test]
+    fn classifies_answers() {
+        let (url, requests) = stub_server(&[
+            ("/2024/day/1/input", "200 OK", "3   4\n4   3\n"),
+            (
+                "/2024/day/2/input",
+                "400 Bad Request",
+                "Puzzle inputs differ by user.  Please log in to get your puzzle input.\n",
+            ),
+            (
+                "/2024/day/3/input",
+                "404 Not Found",
+                "Please don't repeatedly request this endpoint before it unlocks!\n",
+            ),
+            ("/2024/day/4/input", "302 Found", ""),
+            ("/2024/day/5/input", "500 Internal Server Error", "oops"),
+        ]);
+        let fetcher = fetcher(&url, Some("cafe"));
+        assert_eq!(fetcher.input(2024, 1), Ok("3   4\n4   3\n".to_string()));
+        assert_eq!(fetcher.input(2024, 2), Err(Error::SessionExpired));
+        assert_eq!(
+            fetcher.input(2024, 3),
+            Err(Error::NotUnlocked(format!("{url}/2024/day/3/input")))
+        );
+        assert_eq!(fetcher.input(2024, 4), Err(Error::SessionExpired));
+        assert_eq!(
+            fetcher.input(2024, 5),
+            Err(Error::Status {
+                url: format!("{url}/2024/day/5/input"),
+                status: 500
+            })
+        );
+        assert_eq!(
+            fetcher.input(2024, 30),
+            Err(Error::NotFound(format!("{url}/2024/day/30/input")))
+        );
+
+        let requests = requests.lock().unwrap();
+        assert_eq!(requests.len(), 6);
+        assert!(requests[0].contains(&format!("user-agent: {DEFAULT_USER_AGENT}")));
+        assert!(requests[0].contains("cookie: session=cafe"));
+
+        // Inputs differ per user, so there's no point asking without a session.
+        assert_eq!(
+            self::fetcher(&url, None).input(2024, 1),
+            Err(Error::NoSession)
+        );
+    }
+
+    #[test]
+    fn throttles() {
+        let (url, _) = stub_server(&[("/2024/day/1", "200 OK", "<html></html>")]);
+        let fetcher = fetcher(&url, None).with_interval(Duration::from_millis(200));
+        let now = Instant::now();
+        fetcher.puzzle(2024, 1).unwrap();
+        fetcher.puzzle(2024, 1).unwrap();
+        assert!(now.elapsed() >= Duration::from_millis(200));
+    }
+
+    #[test]
+    fn user_agents() {
+        assert_eq!(user_agent(Some("  ")), DEFAULT_USER_AGENT);
+        assert_eq!(
+            user_agent(Some("github.com/someone/aoc")),
+            format!("{DEFAULT_USER_AGENT} (github.com/someone/aoc)")
+        );
+
+        let (url, requests) = stub_server(&[("/2024/day/1", "200 OK", "<html></html>")]);
+        let fetcher = fetcher(&url, None)
+            .with_contact(Some("me@example.com"))
+            .unwrap();
+        fetcher.puzzle(2024, 1).unwrap();
+        let requests = requests.lock().unwrap();
+        assert!(requests[0].contains(&format!(
+            "user-agent: {DEFAULT_USER_AGENT} (me@example.com)"
+        )));
+        assert!(fetcher.with_contact(Some("line\nbreak")).is_err());
+    }
+
+    #[test]
+    fn caches() {
+        let (url, requests) = stub_server(&[
+            ("/2024/day/1/input", "200 OK", "1 2\n"),
+            ("/2024/day/2/input", "400 Bad Request", "Please log in"),
+        ]);
+        let fetcher = fetcher(&url, Some("cafe"));
+        let dir = scratch("caches");
+        let path = dir.join("day01.txt");
+        let load = |fetch| load_or_fetch(&path, fetch, &fetcher, |f| f.input(2024, 1));
+
+        let offline = load(Fetch::Never).unwrap_err();
+        assert_eq!(
+            offline.downcast_ref::<Error>(),
+            Some(&Error::Offline(path.clone()))
+        );
+        assert_eq!(load(Fetch::Missing).unwrap(), "1 2\n");
+        assert_eq!(load(Fetch::Missing).unwrap(), "1 2\n");
+        assert_eq!(load(Fetch::Never).unwrap(), "1 2\n");
+        assert_eq!(requests.lock().unwrap().len(), 1);
+        assert_eq!(load(Fetch::Always).unwrap(), "1 2\n");
+        assert_eq!(requests.lock().unwrap().len(), 2);
+
+        // A failed refetch keeps what was there, and doesn't leave anything behind.
+        std::fs::write(&path, "old\n").unwrap();
+        let expired = load_or_fetch(&path, Fetch::Always, &fetcher, |f| f.input(2024, 2));
+        assert_eq!(
+            expired.unwrap_err().downcast_ref::<Error>(),
+            Some(&Error::SessionExpired)
+        );
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "old\n");
+        assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 1);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
 }
@@ -0,0 +1,363 @@
+//! A terminal player for stepping through a simulation, one frame at a time.
+//!
+//! A day opts in by implementing [`Visualize`] for its state and overriding
+//! [`Runner::visualize`](crate::Runner::visualize) to call [`play`]; it can then be watched with
+//! `cargo run --release -- -d <day> --viz`.
+use std::{collections::VecDeque, time::Duration};
+
+use miette::{IntoDiagnostic, Result};
+use ratatui::{
+    crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind},
+    layout::{Constraint, Direction, Layout},
+    style::{Color, Style},
+    text::{Line, Span, Text},
+    widgets::{Block, Paragraph},
+    DefaultTerminal, Frame,
+};
+
+/// A simulation that can be drawn and advanced by a [`Player`].
+pub trait Visualize {
+    /// The current state, drawn in the main pane.
+    fn render(&self) -> Text<'_>;
+
+    /// Advances one step. Returns `false` once there is nothing left to do.
+    fn step(&mut self) -> bool;
+
+    /// Reacts to a key that the player doesn't use itself, like a manual move. Returns whether the
+    /// state changed.
+    fn handle_key(&mut self, _key: KeyCode) -> bool {
+        false
+    }
+
+    /// Log lines for the message pane, oldest first.
+    fn messages(&self) -> &[String] {
+        &[]
+    }
+
+    fn title(&self) -> String {
+        String::new()
+    }
+}
+
+/// What the event loop should do after a key press.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Flow {
+    Continue,
+    Quit,
+}
+
+const MIN_DELAY: Duration = Duration::from_millis(1);
+const MAX_DELAY: Duration = Duration::from_secs(2);
+const HELP: &str = "space: play/pause  h/l: back/step  +/-: speed  q: quit";
+
+/// Plays a [`Visualize`] in the terminal, keeping the last few states around so it can step
+/// backwards. Every step clones the state, so whatever doesn't change from one step to the next
+/// is best shared, say behind an `Rc`, and logs kept short.
+pub struct Player<V> {
+    history: VecDeque<V>,
+    cursor: usize,
+    /// Number of states that fell off the front of `history`.
+    dropped: usize,
+    max_history: usize,
+    playing: bool,
+    finished: bool,
+    delay: Duration,
+}
+
+impl<V> Player<V>
+where
+    V: Visualize + Clone,
+{
+    pub fn new(state: V) -> Self {
+        Self {
+            history: VecDeque::from([state]),
+            cursor: 0,
+            dropped: 0,
+            max_history: 1000,
+            playing: false,
+            finished: false,
+            delay: Duration::from_millis(100),
+        }
+    }
+
+    /// How many past states to keep for stepping back.
+    #[must_use]
+    pub fn with_history(mut self, max_history: usize) -> Self {
+        self.max_history = max_history.max(1);
+        self
+    }
+
+    #[must_use]
+    pub fn with_delay(mut self, delay: Duration) -> Self {
+        self.delay = delay.clamp(MIN_DELAY, MAX_DELAY);
+        self
+    }
+
+    pub fn current(&self) -> &V {
+        &self.history[self.cursor]
+    }
+
+    /// The step number of the state on screen.
+    pub fn position(&self) -> usize {
+        self.dropped + self.cursor
+    }
+
+    pub fn is_playing(&self) -> bool {
+        self.playing
+    }
+
+    pub fn delay(&self) -> Duration {
+        self.delay
+    }
+
+    fn push(&mut self, state: V) {
+        self.history.truncate(self.cursor + 1);
+        self.history.push_back(state);
+        if self.history.len() > self.max_history {
+            self.history.pop_front();
+            self.dropped += 1;
+        }
+        self.cursor = self.history.len() - 1;
+    }
+
+    /// Moves forward one step, replaying a recorded state if we stepped back earlier.
+    pub fn forward(&mut self) -> bool {
+        if self.cursor + 1 < self.history.len() {
+            self.cursor += 1;
+            return true;
+        }
+        if self.finished {
+            self.playing = false;
+            return false;
+        }
+        let mut next = self.current().clone();
+        if next.step() {
+            self.push(next);
+            true
+        } else {
+            self.finished = true;
+            self.playing = false;
+            false
+        }
+    }
+
+    pub fn back(&mut self) -> bool {
+        self.playing = false;
+        let moved = self.cursor > 0;
+        self.cursor = self.cursor.saturating_sub(1);
+        moved
+    }
+
+    pub fn handle(&mut self, key: KeyEvent) -> Flow {
+        match key.code {
+            KeyCode::Char('q') | KeyCode::Esc => return Flow::Quit,
+            KeyCode::Char(' ') => self.playing = !self.playing && !self.finished,
+            KeyCode::Char('l' | '.') => {
+                self.playing = false;
+                self.forward();
+            }
+            KeyCode::Char('h' | ',') => {
+                self.back();
+            }
+            KeyCode::Char('+' | '=') => self.delay = (self.delay / 2).max(MIN_DELAY),
+            KeyCode::Char('-') => self.delay = (self.delay * 2).min(MAX_DELAY),
+            code => {
+                let mut next = self.current().clone();
+                if next.handle_key(code) {
+                    // A manual change starts a new timeline.
+                    self.finished = false;
+                    self.push(next);
+                }
+            }
+        }
+        Flow::Continue
+    }
+
+    pub fn draw(&self, frame: &mut Frame) {
+        let layout = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Percentage(60),
+                Constraint::Fill(1),
+                Constraint::Length(1),
+            ])
+            .split(frame.area());
+        let state = self.current();
+
+        let board = Paragraph::new(state.render()).block(Block::bordered().title(state.title()));
+        frame.render_widget(board, layout[0]);
+
+        let messages = state.messages();
+        let visible = usize::from(layout[1].height.saturating_sub(2));
+        let lines = messages
+            .iter()
+            .enumerate()
+            .skip(messages.len().saturating_sub(visible))
+            .map(|(i, m)| {
+                Line::from(vec![
+                    Span::styled(i.to_string(), Color::White),
+                    Span::raw(" - "),
+                    Span::styled(m.as_str(), Color::Green),
+                ])
+            })
+            .collect::<Vec<_>>();
+        let messages = Paragraph::new(lines).block(
+            Block::bordered()
+                .title("Messages")
+                .border_style(Style::default().fg(Color::Red)),
+        );
+        frame.render_widget(messages, layout[1]);
+
+        let status = format!(
+            "step {} | {} | {:?} | {HELP}",
+            self.position(),
+            match (self.playing, self.finished) {
+                (true, _) => "playing",
+                (false, true) if self.cursor + 1 == self.history.len() => "done",
+                _ => "paused",
+            },
+            self.delay,
+        );
+        frame.render_widget(Paragraph::new(status), layout[2]);
+    }
+
+    /// Runs the event loop until the user quits, and returns the state that was on screen.
+    pub fn run(mut self, terminal: &mut DefaultTerminal) -> Result<V> {
+        loop {
+            terminal.draw(|f| self.draw(f)).into_diagnostic()?;
+            if self.playing && !event::poll(self.delay).into_diagnostic()? {
+                self.forward();
+                continue;
+            }
+            if let Event::Key(key) = event::read().into_diagnostic()? {
+                if key.kind == KeyEventKind::Press && self.handle(key) == Flow::Quit {
+                    break;
+                }
+            }
+        }
+        Ok(self.history.swap_remove_back(self.cursor).unwrap())
+    }
+}
+
+/// Takes over the terminal and plays `state` until the user quits.
+pub fn play<V>(state: V) -> Result<V>
+where
+    V: Visualize + Clone,
+{
+    let mut terminal = ratatui::init();
+    let result = terminal
+        .clear()
+        .into_diagnostic()
+        .and_then(|_| Player::new(state).run(&mut terminal));
+    ratatui::restore();
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use ratatui::{backend::TestBackend, crossterm::event::KeyModifiers, Terminal};
+
+    use super::*;
+
+    #[derive(Clone)]
+    struct Counter {
+        n: usize,
+        limit: usize,
+        log: Vec<String>,
+    }
+
+    impl Visualize for Counter {
+        fn render(&self) -> Text<'_> {
+            Text::raw(format!("count={}", self.n))
+        }
+
+        fn step(&mut self) -> bool {
+            if self.n == self.limit {
+                return false;
+            }
+            self.n += 1;
+            self.log.push(format!("now {}", self.n));
+            true
+        }
+
+        fn handle_key(&mut self, key: KeyCode) -> bool {
+            match key {
+                KeyCode::Char('0') => {
+                    self.n = 0;
+                    true
+                }
+                _ => false,
+            }
+        }
+
+        fn messages(&self) -> &[String] {
+            &self.log
+        }
+    }
+
+    fn counter(limit: usize) -> Player<Counter> {
+        Player::new(Counter {
+            n: 0,
+            limit,
+            log: Vec::new(),
+        })
+    }
+
+    fn press(player: &mut Player<Counter>, c: char) -> Flow {
+        player.handle(KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE))
+    }
+
+    #[test]
+    fn step_back_and_forth() {
+        let mut player = counter(3);
+        assert!(player.forward());
+        assert!(player.forward());
+        assert!(player.back());
+        assert_eq!(player.current().n, 1);
+        assert!(player.forward());
+        assert!(player.forward());
+        assert!(!player.forward());
+        assert_eq!(player.current().n, 3);
+        assert_eq!(player.position(), 3);
+    }
+
+    #[test]
+    fn keys() {
+        let mut player = counter(10).with_history(2);
+        press(&mut player, 'l');
+        press(&mut player, 'l');
+        press(&mut player, 'l');
+        assert_eq!(player.position(), 3);
+        assert!(player.back());
+        assert!(!player.back());
+
+        press(&mut player, '0');
+        assert_eq!(player.current().n, 0);
+        assert_eq!(player.position(), 3);
+
+        let delay = player.delay();
+        press(&mut player, '+');
+        assert_eq!(player.delay(), delay / 2);
+        press(&mut player, ' ');
+        assert!(player.is_playing());
+        assert_eq!(press(&mut player, 'q'), Flow::Quit);
+    }
+
+    #[test]
+    fn draws_panes() {
+        let mut player = counter(5);
+        player.forward();
+        let mut terminal = Terminal::new(TestBackend::new(80, 12)).unwrap();
+        terminal.draw(|f| player.draw(f)).unwrap();
+        let screen = terminal
+            .backend()
+            .buffer()
+            .content()
+            .iter()
+            .map(|c| c.symbol())
+            .collect::<String>();
+        assert!(screen.contains("count=1"));
+        assert!(screen.contains("0 - now 1"));
+        assert!(screen.contains("step 1 | paused"));
+    }
+}